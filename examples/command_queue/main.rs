@@ -0,0 +1,61 @@
+use std::sync::OnceLock;
+
+use grug_rs::{Arguments, Command, Grug, GrugValue, ReentrancyPolicy};
+
+use anyhow::Result;
+use grug_rs_proc_macro::game_function;
+
+static GRUG: OnceLock<Grug> = OnceLock::new();
+
+fn main() -> Result<()> {
+    // Initializes grug
+    let mut grug = Grug::new(
+        None,
+        "./examples/command_queue/mod_api.json",
+        "./examples/command_queue/mods",
+        "./examples/command_queue/mods_dll",
+        1000,
+    )?;
+
+    // Activations from inside of game functions get queued instead of erroring
+    grug.set_reentrancy_policy(ReentrancyPolicy::Defer);
+
+    let grug = GRUG.get_or_init(|| grug);
+
+    grug.activate_on_function("World", "on_update", &mut Arguments::empty())?;
+    grug.flush_commands(|command| match command {
+        Command::Spawn { entity } => println!("Spawning {entity}"),
+        Command::Despawn { id } => println!("Despawning {id}"),
+        Command::Activate { .. } | Command::ActivateEntity { .. } => unreachable!(),
+    })?;
+    Ok(())
+}
+
+#[game_function]
+fn println(message: String) {
+    println!("{message}");
+}
+
+#[game_function]
+fn spawn(name: String) {
+    let grug = GRUG.get().unwrap();
+
+    // Reentrancy is tracked per thread, other threads would wait for this `on_function` instead
+    assert!(grug.is_in_on_function());
+    std::thread::scope(|scope| {
+        scope.spawn(|| assert!(!grug.is_in_on_function()));
+    });
+
+    grug.commands().push(Command::Spawn {
+        entity: name.to_string(),
+    });
+}
+
+#[game_function]
+fn greet(message: String) {
+    let mut args = Arguments::new(vec![GrugValue::String(message.to_string())]);
+    GRUG.get()
+        .unwrap()
+        .activate_on_function("World", "on_greet", &mut args)
+        .unwrap();
+}
@@ -0,0 +1,53 @@
+use std::sync::Mutex;
+
+use grug_rs::{Arguments, EntityRef, Grug, ModProfile};
+
+use anyhow::Result;
+use grug_rs_proc_macro::{error_handler, game_function};
+
+static SPAWNED: Mutex<Vec<String>> = Mutex::new(vec![]);
+static ERRORS: Mutex<Vec<String>> = Mutex::new(vec![]);
+
+fn main() -> Result<()> {
+    // Initializes grug
+    let mut grug = Grug::new(
+        Some(error_handler),
+        "./examples/entity_ref/mod_api.json",
+        "./examples/entity_ref/mods",
+        "./examples/entity_ref/mods_dll",
+        1000,
+    )?;
+
+    grug.activate_on_function("World", "on_update", &mut Arguments::empty())?;
+    assert_eq!(*SPAWNED.lock().unwrap(), ["base:goblin", "extra:dragon"]);
+    assert!(ERRORS.lock().unwrap().is_empty());
+
+    // Entities of disabled mods can't be spawned anymore
+    let mut profile = ModProfile::from_mods(grug.mods());
+    profile.set_enabled("extra", false);
+    grug.set_profile(profile)?;
+
+    SPAWNED.lock().unwrap().clear();
+    grug.activate_on_function("World", "on_update", &mut Arguments::empty())?;
+
+    assert_eq!(*SPAWNED.lock().unwrap(), ["base:goblin"]);
+    assert!(ERRORS.lock().unwrap()[0].contains("`extra:dragon` is not a loaded entity"));
+
+    Ok(())
+}
+
+#[error_handler]
+fn error_handler(reason: String, ty: GrugRuntimeError, on_fn_name: String, on_fn_path: String) {
+    eprintln!("Grug runtime error: {reason}\n  at {on_fn_name} ({on_fn_path})");
+    ERRORS.lock().unwrap().push(reason);
+}
+
+#[game_function(api = "examples/entity_ref/mod_api.json")]
+fn spawn(enemy: EntityRef) {
+    println!(
+        "Spawning {} from {}",
+        enemy.entity(),
+        enemy.file().relative_path().display()
+    );
+    SPAWNED.lock().unwrap().push(enemy.entity().to_string());
+}
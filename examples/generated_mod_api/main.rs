@@ -0,0 +1,76 @@
+use grug_rs::{
+    Arguments, EntityRef, Grug, ModApiBuilder,
+    mod_api_type::{GrugType, ModAPI},
+    resource::Resource,
+};
+
+use anyhow::Result;
+use grug_rs_proc_macro::game_function;
+
+fn main() -> Result<()> {
+    // Fill in the game functions of the mod API from the ones registered below
+    let mod_api =
+        ModApiBuilder::from_mod_api(ModAPI::load("./examples/generated_mod_api/mod_api.json")?)
+            .registered_game_functions()
+            .build();
+
+    assert_eq!(
+        mod_api.game_functions.keys().collect::<Vec<_>>(),
+        ["add", "greet", "print_path", "println", "println_int"]
+    );
+    assert_eq!(
+        mod_api.game_functions["print_path"].arguments[0].type_,
+        GrugType::Resource {
+            extension: ".txt".to_string()
+        }
+    );
+    assert_eq!(
+        mod_api.game_functions["greet"].arguments[0].type_,
+        GrugType::Entity {
+            entity_type: "World".to_string()
+        }
+    );
+
+    let mod_api_path = std::env::temp_dir().join("generated_mod_api.json");
+    mod_api.save(&mod_api_path)?;
+
+    // Initializes grug
+    let grug = Grug::new(
+        None,
+        mod_api_path,
+        "./examples/generated_mod_api/mods",
+        "./examples/generated_mod_api/mods_dll",
+        1000,
+    )?;
+
+    grug.activate_on_function("World", "on_update", &mut Arguments::empty())?;
+    Ok(())
+}
+
+#[game_function(description = "Prints a string with a new line")]
+fn println(message: String) {
+    println!("{message}");
+}
+
+#[game_function(description = "Prints an i32 with a new line")]
+fn println_int(number: i32) {
+    println!("{number}");
+}
+
+#[game_function(
+    description = "Prints the path of a text file",
+    resource_extension(file = ".txt")
+)]
+fn print_path(file: Resource) {
+    println!("{}", file.relative_path().display());
+}
+
+#[game_function(description = "Greets a world", entity_type(world = "World"))]
+fn greet(world: EntityRef) {
+    println!("Hello {}", world.entity());
+}
+
+#[game_function(description = "Adds two numbers")]
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
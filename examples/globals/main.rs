@@ -0,0 +1,30 @@
+use std::sync::atomic::{AtomicI32, Ordering};
+
+use grug_rs::{Arguments, Grug};
+
+use anyhow::Result;
+use grug_rs_proc_macro::game_function;
+
+static REPORTED: AtomicI32 = AtomicI32::new(0);
+
+fn main() -> Result<()> {
+    // Initializes grug
+    let grug = Grug::new(
+        None,
+        "./examples/globals/mod_api.json",
+        "./examples/globals/mods",
+        "./examples/globals/mods_dll",
+        1000,
+    )?;
+
+    // `on_count` has no arguments, but still reads the `count` global
+    grug.activate_on_function("Counter", "on_count", &mut Arguments::empty())?;
+    assert_eq!(REPORTED.load(Ordering::Relaxed), 42);
+
+    Ok(())
+}
+
+#[game_function(api = "examples/globals/mod_api.json")]
+fn report(count: i32) {
+    REPORTED.store(count, Ordering::Relaxed);
+}
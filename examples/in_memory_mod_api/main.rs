@@ -0,0 +1,46 @@
+use grug_rs::{
+    Arguments, Grug, GrugValue,
+    mod_api_type::{GameFunction, ModAPI},
+};
+
+use anyhow::Result;
+use grug_rs_proc_macro::game_function;
+
+fn main() -> Result<()> {
+    let mod_api = ModAPI::builder()
+        .entity("World", "Let's print in here")
+        .on_function("on_update", GameFunction::new("Called every tick"))
+        .on_function(
+            "on_message",
+            GameFunction::new("Called with a message").argument("message", "string"),
+        )
+        .game_function(
+            "println",
+            GameFunction::new("Prints a string with a new line").argument("msg", "string"),
+        )
+        .build();
+
+    // Initializes grug without a mod_api.json
+    let grug = Grug::from_mod_api(
+        None,
+        mod_api,
+        "./examples/in_memory_mod_api/mods",
+        "./examples/in_memory_mod_api/mods_dll",
+        1000,
+    )?;
+
+    grug.activate_on_function("World", "on_update", &mut Arguments::empty())?;
+    grug.activate_on_function(
+        "World",
+        "on_message",
+        &mut Arguments::new(vec![GrugValue::String(
+            "Built with ModAPI::builder".to_string(),
+        )]),
+    )?;
+    Ok(())
+}
+
+#[game_function]
+fn println(message: String) {
+    println!("{message}");
+}
@@ -0,0 +1,31 @@
+use grug_rs::{GrugError, schema};
+
+use anyhow::Result;
+
+fn main() -> Result<()> {
+    // Point your editor at these
+    let out_dir = std::env::temp_dir().join("grug_rs_schemas");
+    schema::generate(&out_dir)?;
+    println!("Wrote the schemas to `{}`", out_dir.display());
+
+    schema::check_mod_api("./examples/json_schema/mod_api.json")?;
+    schema::check_mod_info("./examples/json_schema/mods/hello/about.json")?;
+
+    // Errors point at where the mistake is
+    let path = std::env::temp_dir().join("grug_rs_broken_about.json");
+    std::fs::write(
+        &path,
+        r#"{ "name": "hello", "version": 1, "game_version": "1.0.0" }"#,
+    )?;
+
+    let Err(error) = schema::check_mod_info(&path) else {
+        unreachable!()
+    };
+    let GrugError::SchemaViolations { ref errors, .. } = error else {
+        unreachable!()
+    };
+    assert!(errors.iter().any(|error| error.starts_with("`/version`")));
+    println!("{error}");
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use grug_rs::{Arguments, Grug, GrugError, mod_api_type::ModAPI};
+
+use anyhow::Result;
+use grug_rs_proc_macro::game_function;
+
+fn main() -> Result<()> {
+    // Converted to `mods_dll/mod_api.json` for grug
+    let grug = Grug::new(
+        None,
+        "./examples/mod_api_formats/mod_api.toml",
+        "./examples/mod_api_formats/mods",
+        "./examples/mod_api_formats/mods_dll",
+        1000,
+    )?;
+
+    grug.activate_on_function("World", "on_update", &mut Arguments::empty())?;
+
+    let yaml = ModAPI::load("./examples/mod_api_formats/mod_api.yaml")?;
+    assert!(ModAPI::diff(grug.mod_api(), &yaml).is_empty());
+
+    // Errors point at where the mistake is
+    let path = std::env::temp_dir().join("grug_rs_broken_mod_api.toml");
+    std::fs::write(
+        &path,
+        "[game_functions.println]\ndescription = \"Prints\"\narguments = [{ name = \"msg\" }]\n",
+    )?;
+
+    let Err(error) = ModAPI::load(&path) else {
+        unreachable!()
+    };
+    assert!(matches!(
+        error,
+        GrugError::Deserialize {
+            line: 3,
+            column: 14,
+            ..
+        }
+    ));
+    println!("{error}");
+
+    Ok(())
+}
+
+#[game_function]
+fn println(message: String) {
+    println!("{message}");
+}
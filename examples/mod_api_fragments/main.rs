@@ -0,0 +1,42 @@
+use grug_rs::{Arguments, Grug, GrugError, mod_api_type::ModAPI};
+
+use anyhow::Result;
+use grug_rs_proc_macro::game_function;
+
+fn main() -> Result<()> {
+    // Merges every fragment in `mod_api` and writes it to `mods_dll/mod_api.json` for grug
+    let grug = Grug::from_mod_api_dir(
+        None,
+        "./examples/mod_api_fragments/mod_api",
+        "./examples/mod_api_fragments/mods",
+        "./examples/mod_api_fragments/mods_dll",
+        1000,
+    )?;
+
+    // Fragments are merged sorted by file name
+    assert_eq!(
+        grug.mod_api().entities.keys().collect::<Vec<_>>(),
+        ["Enemy", "World"]
+    );
+
+    grug.activate_on_function("World", "on_update", &mut Arguments::empty())?;
+    grug.activate_on_function("Enemy", "on_update", &mut Arguments::empty())?;
+
+    // Two fragments can't define the same entity or game function
+    let mut mod_api = ModAPI::load("./examples/mod_api_fragments/mod_api/world.json")?;
+    let Err(error) = mod_api.merge(grug.mod_api().clone()) else {
+        unreachable!()
+    };
+    assert!(matches!(
+        error,
+        GrugError::ModAPIConflicts { ref conflicts } if conflicts == &["`entities.World` is defined by both mod APIs"]
+    ));
+    println!("{error}");
+
+    Ok(())
+}
+
+#[game_function(api = "examples/mod_api_fragments/mod_api/game_functions.json")]
+fn println(message: String) {
+    println!("{message}");
+}
@@ -0,0 +1,76 @@
+use grug_rs::{
+    Arguments, Grug, ModAPIReload,
+    mod_api_type::{GameFunction, ModAPI},
+};
+
+use anyhow::Result;
+use grug_rs_proc_macro::game_function;
+
+const MOD_API_PATH: &str = "./examples/mod_api_reload/mods_dll/mod_api.json";
+
+fn main() -> Result<()> {
+    let mod_api = ModAPI::builder()
+        .entity("World", "Let's print in here")
+        .on_function("on_update", GameFunction::new("Called every tick"))
+        .game_function(
+            "println",
+            GameFunction::new("Prints a string with a new line").argument("msg", "string"),
+        )
+        .build();
+
+    // Writes the mod API to `MOD_API_PATH`
+    let mut grug = Grug::from_mod_api(
+        None,
+        mod_api.clone(),
+        "./examples/mod_api_reload/mods",
+        "./examples/mod_api_reload/mods_dll",
+        1000,
+    )?;
+
+    grug.activate_on_function("World", "on_update", &mut Arguments::empty())?;
+    assert_eq!(grug.reload_mod_api_if_modified()?, ModAPIReload::Unchanged);
+
+    // Descriptions can change while running
+    let mut edited = mod_api.clone();
+    edited.entities["World"].description = "The whole world".to_string();
+    edited.save(MOD_API_PATH)?;
+
+    assert_eq!(grug.reload_mod_api()?, ModAPIReload::Reloaded);
+    assert_eq!(
+        grug.mod_api().entities["World"].description,
+        "The whole world"
+    );
+
+    // Mods are compiled against the rest, so that needs a restart
+    let mut edited = grug.mod_api().clone();
+    edited.entities["World"].on_functions.insert(
+        "on_draw".to_string(),
+        GameFunction::new("Called every frame"),
+    );
+    edited.save(MOD_API_PATH)?;
+
+    let ModAPIReload::RestartRequired { changes } = grug.reload_mod_api()? else {
+        unreachable!()
+    };
+    assert_eq!(changes.len(), 1);
+    assert_eq!(
+        changes[0].to_string(),
+        "`entities.World.on_functions.on_draw` was added"
+    );
+    println!("Restart to load the mod API change: {}", changes[0]);
+
+    // The old mod API is kept
+    assert!(
+        !grug.mod_api().entities["World"]
+            .on_functions
+            .contains_key("on_draw")
+    );
+    grug.activate_on_function("World", "on_update", &mut Arguments::empty())?;
+
+    Ok(())
+}
+
+#[game_function]
+fn println(message: String) {
+    println!("{message}");
+}
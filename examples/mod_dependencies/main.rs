@@ -0,0 +1,26 @@
+use grug_rs::{Arguments, Grug};
+
+use anyhow::Result;
+use grug_rs_proc_macro::game_function;
+
+fn main() -> Result<()> {
+    // Initializes grug
+    let grug = Grug::new(
+        None,
+        "./examples/mod_dependencies/mod_api.json",
+        "./examples/mod_dependencies/mods",
+        "./examples/mod_dependencies/mods_dll",
+        1000,
+    )?;
+
+    // `addon` depends on `library`, so it's loaded after it
+    assert_eq!(grug.load_order(), ["library", "addon"]);
+
+    grug.activate_on_function("World", "on_update", &mut Arguments::empty())?;
+    Ok(())
+}
+
+#[game_function]
+fn println(message: String) {
+    println!("{message}");
+}
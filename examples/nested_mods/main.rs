@@ -0,0 +1,82 @@
+use grug_rs::{Arguments, Grug, GrugError, ModProfile, mod_conflicts::ConflictPolicy};
+
+use anyhow::Result;
+use grug_rs_proc_macro::game_function;
+
+fn main() -> Result<()> {
+    // Initializes grug
+    let mut grug = Grug::new(
+        None,
+        "./examples/nested_mods/mod_api.json",
+        "./examples/nested_mods/mods",
+        "./examples/nested_mods/mods_dll",
+        1000,
+    )?;
+
+    // Runs the files in sub-directories too, sorted by path
+    grug.activate_on_function("Enemy", "on_update", &mut Arguments::empty())?;
+
+    assert_eq!(
+        enemies(&grug),
+        [
+            "base:dragon",
+            "base:goblin",
+            "base:slime",
+            "extra:bat",
+            "extra:goblin"
+        ]
+    );
+
+    // Both mods define a goblin, so only run the one of the mod loaded last
+    assert_eq!(grug.conflicts()[0].mods(), ["base", "extra"]);
+    grug.set_conflict_policy(ConflictPolicy::LastWins);
+
+    assert_eq!(
+        enemies(&grug),
+        ["base:dragon", "base:slime", "extra:bat", "extra:goblin"]
+    );
+
+    // Switch the load order at runtime by loading `extra` first
+    let mut profile = ModProfile::from_mods(grug.mods());
+    profile.move_to("extra", 0);
+    grug.set_profile(profile.clone())?;
+
+    assert_eq!(
+        enemies(&grug),
+        ["extra:bat", "base:dragon", "base:goblin", "base:slime"]
+    );
+
+    // Disabling a mod stops its files from being run
+    profile.set_enabled("extra", false);
+    grug.set_profile(profile)?;
+
+    assert_eq!(enemies(&grug), ["base:dragon", "base:goblin", "base:slime"]);
+    grug.activate_on_function("Enemy", "on_update", &mut Arguments::empty())?;
+
+    // Run a single entity by its mod-qualified name
+    grug.activate_entity_on_function("base:goblin", "on_update", &mut Arguments::empty())?;
+
+    // Unknown entities suggest close matches
+    let Err(error) = grug.get_file_by_entity("goblin") else {
+        unreachable!()
+    };
+    assert!(matches!(
+        error,
+        GrugError::UnknownEntity { ref suggestions, .. } if suggestions == &["base:goblin"]
+    ));
+    println!("{error}");
+
+    Ok(())
+}
+
+fn enemies(grug: &Grug) -> Vec<String> {
+    grug.get_files_by_entity_type("Enemy")
+        .iter()
+        .map(|file| file.entity().to_string())
+        .collect()
+}
+
+#[game_function]
+fn println(message: String) {
+    println!("{message}");
+}
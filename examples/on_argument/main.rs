@@ -16,16 +16,16 @@ fn main() -> Result<()> {
     let mut args = Arguments::new(vec![GrugValue::String("hello, world".to_string())]);
     grug.activate_on_function("World", "on_update", &mut Arguments::empty())?;
     grug.activate_on_function("World", "on_argument_test", &mut args)?;
-    grug.activate_on_function("World", "on_globals_test", &mut args)?;
+    grug.activate_on_function("World", "on_globals_test", &mut Arguments::empty())?;
     Ok(())
 }
 
-#[game_function]
+#[game_function(api = "examples/on_argument/mod_api.json")]
 fn println(message: String) {
     println!("{message}");
 }
 
-#[game_function]
+#[game_function(api = "examples/on_argument/mod_api.json")]
 fn println_int(message: i32) {
     println!("{message}");
 }
@@ -0,0 +1,45 @@
+use grug_rs::{
+    Arguments, Grug,
+    resource::{Asset, Resource, register_loader},
+};
+
+use anyhow::Result;
+use grug_rs_proc_macro::game_function;
+
+struct Text(String);
+
+fn main() -> Result<()> {
+    // Lets game functions take an `Asset<Text>`, every file is only loaded once
+    register_loader(|resource| {
+        println!("Loading {}", resource.relative_path().display());
+        std::fs::read_to_string(resource.path()).map(Text)
+    });
+
+    // Initializes grug
+    let grug = Grug::new(
+        None,
+        "./examples/resources/mod_api.json",
+        "./examples/resources/mods",
+        "./examples/resources/mods_dll",
+        1000,
+    )?;
+
+    grug.activate_on_function("World", "on_update", &mut Arguments::empty())?;
+    Ok(())
+}
+
+#[game_function(api = "examples/resources/mod_api.json")]
+fn print_path(file: Resource) {
+    assert!(file.path().is_absolute());
+    println!(
+        "`{}` of `{}` is at {}",
+        file.relative_path().display(),
+        file.mod_name(),
+        file.path().display()
+    );
+}
+
+#[game_function(api = "examples/resources/mod_api.json")]
+fn say(text: Asset<Text>) {
+    print!("{}", text.0);
+}
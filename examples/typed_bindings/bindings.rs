@@ -0,0 +1,33 @@
+// Generated by `grug_rs::build`, don't edit
+
+#[doc = "Let's print in here"]
+#[derive(Clone, Copy)]
+pub struct World<'grug> {
+    grug: &'grug ::grug::Grug,
+}
+
+impl<'grug> World<'grug> {
+    pub const ENTITY_TYPE: &'static str = "World";
+
+    pub fn new(grug: &'grug ::grug::Grug) -> Self {
+        Self { grug }
+    }
+
+    #[doc = "Called every tick"]
+    pub fn on_update(&self) -> Result<(), ::grug::GrugError> {
+        self.grug.activate_on_function(
+            "World",
+            "on_update",
+            &mut ::grug::Arguments::new(vec![]),
+        )
+    }
+
+    #[doc = "Called when something hits the world"]
+    pub fn on_hit(&self, source: String) -> Result<(), ::grug::GrugError> {
+        self.grug.activate_on_function(
+            "World",
+            "on_hit",
+            &mut ::grug::Arguments::new(vec![::grug::GrugValue::String(source)]),
+        )
+    }
+}
@@ -0,0 +1,35 @@
+// A game depending on `grug = { package = "grug-rs" }` would use the name `grug` directly
+extern crate grug_rs as grug;
+
+use grug::Grug;
+
+use anyhow::Result;
+use grug_rs_proc_macro::game_function;
+
+/// What `grug_rs::build::generate_renamed("mod_api.json", "grug")` writes in a build script,
+/// checked in so this example doesn't need one
+mod mod_api {
+    include!("bindings.rs");
+}
+
+fn main() -> Result<()> {
+    // Initializes grug
+    let grug = Grug::new(
+        None,
+        "./examples/typed_bindings/mod_api.json",
+        "./examples/typed_bindings/mods",
+        "./examples/typed_bindings/mods_dll",
+        1000,
+    )?;
+
+    let world = mod_api::World::new(&grug);
+    println!("Every {} gets updated and hit", mod_api::World::ENTITY_TYPE);
+    world.on_update()?;
+    world.on_hit("Hit by a meteor".to_string())?;
+    Ok(())
+}
+
+#[game_function(api = "examples/typed_bindings/mod_api.json")]
+fn println(message: String) {
+    println!("{message}");
+}
@@ -1,16 +1,24 @@
-use std::{collections::HashMap, mem::swap};
+use std::{
+    collections::HashMap,
+    mem::swap,
+    path::{Path, PathBuf},
+};
 
 use proc_macro::TokenStream;
+use proc_macro_crate::{FoundCrate, crate_name};
+use proc_macro2::Span;
 use quote::{ToTokens, quote};
+use serde_json::Value;
 use syn::{
-    Abi, Block, FnArg, Ident, ItemFn, Pat, Stmt, Type, TypePtr, parse_macro_input,
+    Abi, Block, Error, FnArg, Ident, ItemFn, LitStr, Pat, ReturnType, Signature, Stmt, Type,
+    TypePtr, meta, parse_macro_input,
     token::{Const, Star, Unsafe},
 };
 
 /// Attribute to make error handlers easily
 ///
 /// # Example
-/// ```
+/// ```rs
 /// #[error_handler]
 /// fn error_handler(reason: String, ty: GrugRuntimeError, on_fn_name: String, on_fn_path: String) {
 ///     eprintln!(
@@ -63,7 +71,7 @@ pub fn error_handler(_attr: TokenStream, item: TokenStream) -> TokenStream {
         \"<unknown>\".into()
     }}.to_string();
 
-    let {1} = unsafe {{ std::mem::transmute::<_, grug_rs::GrugRuntimeError>({1}) }};
+    let {1} = unsafe {{ std::mem::transmute::<_, {private}::GrugRuntimeError>({1}) }};
 
     let {2} = if !{2}.is_null() {{
         unsafe {{ std::ffi::CStr::from_ptr({2}).to_string_lossy() }}
@@ -77,7 +85,11 @@ pub fn error_handler(_attr: TokenStream, item: TokenStream) -> TokenStream {
         \"<unknown>\".into()
     }}.to_string();
 }}",
-        names[0], names[1], names[2], names[3]
+        names[0],
+        names[1],
+        names[2],
+        names[3],
+        private = private_path(),
     )
     .parse()
     .unwrap();
@@ -100,14 +112,27 @@ pub fn error_handler(_attr: TokenStream, item: TokenStream) -> TokenStream {
 
 fn grab_type_for_error_handler(idx: usize) -> TokenStream {
     match idx {
-        0 => "*const std::ffi::c_char",
-        1 => "grug_rs::grug_sys::grug_runtime_error_type",
-        2 => "*const std::ffi::c_char",
-        3 => "*const std::ffi::c_char",
+        0 | 2 | 3 => "*const std::ffi::c_char".parse().unwrap(),
+        1 => {
+            let private = private_path();
+            quote! { #private::grug_runtime_error_type }.into()
+        }
         _ => unreachable!(),
     }
-    .parse()
-    .unwrap()
+}
+
+/// The path to the `__private` module of grug-rs, which everything generated goes through
+///
+/// Looked up in the `Cargo.toml` being compiled, so it works when grug-rs is renamed there.
+fn private_path() -> proc_macro2::TokenStream {
+    let name = match crate_name("grug-rs") {
+        Ok(FoundCrate::Name(name)) => name,
+        // grug-rs's own examples and tests, which reach it through `extern crate self as grug_rs`
+        Ok(FoundCrate::Itself) | Err(_) => "grug_rs".to_string(),
+    };
+    let name = Ident::new(&name, Span::call_site());
+
+    quote! { ::#name::__private }
 }
 
 /// Attribute to make game function easily
@@ -115,21 +140,122 @@ fn grab_type_for_error_handler(idx: usize) -> TokenStream {
 /// Only appliable to functions
 ///
 /// # Example
-/// ```
+/// ```rs
 /// #[game_function]
 /// fn println(message: String) {
 ///     println!("{message}");
 /// }
 /// ```
+///
+/// Pass `description` to register it for `grug_rs::ModApiBuilder`,
+/// which can then write it to the `game_functions` of a `mod_api.json`:
+/// ```rs
+/// #[game_function(description = "Prints a string with a new line")]
+/// fn println(message: String) {
+///     println!("{message}");
+/// }
+/// ```
+///
+/// Resource arguments need their extension and entity arguments can name their entity type,
+/// since the Rust types don't say which. When one can't be resolved the game function returns
+/// early, so it can only return nothing, a number, a `bool` or a pointer:
+/// ```rs
+/// #[game_function(
+///     description = "Draws a sprite on an entity",
+///     resource_extension(sprite = ".png"),
+///     entity_type(target = "Enemy")
+/// )]
+/// fn draw(sprite: Resource, target: EntityRef) {
+///     println!("{}", sprite.relative_path().display());
+/// }
+/// ```
+///
+/// Pass `api` to check the name, arguments and return type against a `mod_api.json`,
+/// relative to your `Cargo.toml`. Only JSON is supported, so for a mod API in TOML, YAML
+/// or fragments, point it at the `mod_api.json` grug-rs converts it to:
+/// ```rs
+/// #[game_function(api = "mod_api.json")]
+/// fn println(message: String) {
+///     println!("{message}");
+/// }
+/// ```
 #[proc_macro_attribute]
-pub fn game_function(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn game_function(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut api = None;
+    let mut description = None;
+    let mut details = vec![];
+    let parser = meta::parser(|meta| {
+        if meta.path.is_ident("api") {
+            api = Some(meta.value()?.parse::<LitStr>()?);
+            Ok(())
+        } else if meta.path.is_ident("description") {
+            description = Some(meta.value()?.parse::<LitStr>()?);
+            Ok(())
+        } else if let Some(kind @ ("resource_extension" | "entity_type")) = meta
+            .path
+            .get_ident()
+            .map(|ident| ident.to_string())
+            .as_deref()
+        {
+            let kind = if kind == "resource_extension" {
+                DetailKind::ResourceExtension
+            } else {
+                DetailKind::EntityType
+            };
+
+            meta.parse_nested_meta(|argument| {
+                details.push(ArgumentDetail {
+                    kind,
+                    argument: argument.path.require_ident()?.clone(),
+                    value: argument.value()?.parse::<LitStr>()?,
+                });
+                Ok(())
+            })
+        } else {
+            Err(meta.error("unsupported game_function property"))
+        }
+    });
+    parse_macro_input!(attr with parser);
+
     let mut input = parse_macro_input!(item as ItemFn);
 
+    // Checked before the signature is turned into C types
+    let api_check = match &api {
+        Some(api) => match check_against_api(api, &input.sig) {
+            Ok(tokens) => tokens,
+            Err(error) => error.to_compile_error(),
+        },
+        None => quote! {},
+    };
+
+    let registration = match &description {
+        Some(description) => match register(description, &details, &input.sig) {
+            Ok(tokens) => tokens,
+            Err(error) => error.to_compile_error(),
+        },
+        None => match details.first() {
+            Some(detail) => Error::new_spanned(
+                &detail.argument,
+                format!(
+                    "`{}` is only used to register the game function, which needs `description`",
+                    detail.kind.name()
+                ),
+            )
+            .to_compile_error(),
+            None => quote! {},
+        },
+    };
+
+    let fn_name = input.sig.ident.to_string();
+    let private = private_path();
+
     let args = &mut input.sig.inputs;
 
     let mut types = HashMap::new();
+    // The index of every resource and entity argument, how it's resolved and its Rust type
+    let mut resolved = HashMap::new();
 
-    for arg in args {
+    for (i, arg) in args.iter_mut().enumerate() {
         if let FnArg::Typed(pattern) = arg {
             match *pattern.ty.clone() {
                 Type::Path(type_path) => {
@@ -144,6 +270,8 @@ pub fn game_function(_attr: TokenStream, item: TokenStream) -> TokenStream {
 
                     let type_name = type_path.path.to_token_stream().to_string();
 
+                    let last_segment = type_path.path.segments.last();
+
                     // Ensure it's valid type
                     if type_name == "String" {
                         types.insert(var_name, "String".to_string());
@@ -154,6 +282,41 @@ pub fn game_function(_attr: TokenStream, item: TokenStream) -> TokenStream {
                             &mut *pattern.ty,
                             &mut Type::Ptr(parse_macro_input!(c_string_type as TypePtr)),
                         );
+                    } else if let Some(segment) = last_segment
+                        && (segment.ident == "Resource" || segment.ident == "Asset")
+                    {
+                        // Resources are strings, resolved to a `Resource` or loaded as an `Asset<T>`
+                        let load = if segment.ident == "Asset" {
+                            format!(".and_then(|resource| {private}::load(&resource))")
+                        } else {
+                            String::new()
+                        };
+
+                        types.insert(var_name.clone(), "Resolved".to_string());
+                        resolved.insert(
+                            var_name,
+                            (i, format!("{private}::Resource"), load, type_name.clone()),
+                        );
+
+                        let c_string_type = "*const std::ffi::c_char".parse().unwrap();
+                        *pattern.ty = Type::Ptr(parse_macro_input!(c_string_type as TypePtr));
+                    } else if let Some(segment) = last_segment
+                        && segment.ident == "EntityRef"
+                    {
+                        // Entities are strings, resolved to the file of a loaded entity
+                        types.insert(var_name.clone(), "Resolved".to_string());
+                        resolved.insert(
+                            var_name,
+                            (
+                                i,
+                                format!("{private}::EntityRef"),
+                                String::new(),
+                                type_name.clone(),
+                            ),
+                        );
+
+                        let c_string_type = "*const std::ffi::c_char".parse().unwrap();
+                        *pattern.ty = Type::Ptr(parse_macro_input!(c_string_type as TypePtr));
                     }
                 }
                 Type::Reference(reference) => {
@@ -192,8 +355,39 @@ pub fn game_function(_attr: TokenStream, item: TokenStream) -> TokenStream {
         }
     }
 
+    // Game functions return early when an argument can't be resolved
+    let error_return = if resolved.is_empty() {
+        String::new()
+    } else {
+        match resolve_error_return(&input.sig) {
+            Ok(tokens) => tokens.to_string(),
+            Err(error) => return error.to_compile_error().into(),
+        }
+    };
+
     for (name, type_) in types {
-        if type_ == "String" {
+        if type_ == "Resolved" {
+            let (index, resolver, load, rust_type) = &resolved[&name];
+
+            let to_resolved = format!(
+                "let {name}: {rust_type} = match unsafe {{
+                    {resolver}::from_game_function({name}, \"{fn_name}\", {index})
+                }}{load} {{
+                    Ok(value) => value,
+                    Err(error) => {{
+                        {private}::game_function_error(&error);
+                        {error_return}
+                    }}
+                }};"
+            )
+            .parse()
+            .unwrap();
+
+            input
+                .block
+                .stmts
+                .insert(0, parse_macro_input!(to_resolved as Stmt));
+        } else if type_ == "String" {
             // Only need to modify string types
             let to_string = format!(
                 "    let {0} = if !{0}.is_null() {{
@@ -263,7 +457,541 @@ pub fn game_function(_attr: TokenStream, item: TokenStream) -> TokenStream {
     input.sig.ident = parse_macro_input!(ident as Ident);
 
     TokenStream::from(quote! {
+        #api_check
+        #registration
+        #private::inventory::submit! {
+            #private::ImplementedGameFunction { name: #fn_name }
+        }
         #[unsafe(no_mangle)]
         #input
     })
 }
+
+/// What a game function returns when one of its arguments can't be resolved
+///
+/// grug stops the `on_function` after a game function error, so the value is never used,
+/// but it has to exist for every return type.
+fn resolve_error_return(sig: &Signature) -> Result<proc_macro2::TokenStream, Error> {
+    let ty = match &sig.output {
+        ReturnType::Default => return Ok(quote! { return; }),
+        ReturnType::Type(_, ty) => ty,
+    };
+
+    match &**ty {
+        Type::Ptr(pointer) if pointer.mutability.is_some() => {
+            Ok(quote! { return std::ptr::null_mut(); })
+        }
+        Type::Ptr(_) => Ok(quote! { return std::ptr::null(); }),
+        Type::Path(type_path) if PRIMITIVES.iter().any(|x| type_path.path.is_ident(x)) => {
+            Ok(quote! { return Default::default(); })
+        }
+        _ => Err(Error::new_spanned(
+            ty,
+            format!(
+                "`{}` returns early when a `Resource`, `Asset` or `EntityRef` argument can't be resolved, \
+                so it can only return nothing, a number, a `bool` or a pointer, not `{}`",
+                sig.ident,
+                ty.to_token_stream()
+            ),
+        )),
+    }
+}
+
+const PRIMITIVES: [&str; 15] = [
+    "bool", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+    "f32", "f64",
+];
+
+/// Checks a game function against its entry in `game_functions` of a mod API
+///
+/// Returns an `include_bytes!` of the mod API so it gets checked again when it changes.
+fn check_against_api(api: &LitStr, sig: &Signature) -> Result<proc_macro2::TokenStream, Error> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let path = PathBuf::from(manifest_dir).join(api.value());
+
+    let mod_api = read_mod_api(&path).map_err(|error| Error::new_spanned(api, error))?;
+    check_signature(&mod_api, &api.value(), sig)?;
+
+    let path = path.to_string_lossy();
+    Ok(quote! {
+        const _: &[u8] = include_bytes!(#path);
+    })
+}
+
+/// Reads a `mod_api.json`
+///
+/// Mod APIs in TOML, YAML or split into fragments would need grug-rs to read them,
+/// which this crate can't depend on, so they're rejected instead of failing to parse as JSON.
+fn read_mod_api(path: &Path) -> Result<Value, String> {
+    if path.is_dir() || path.extension().is_none_or(|extension| extension != "json") {
+        return Err(format!(
+            "Only JSON is supported by `api =`, but `{}` isn't a `.json` file. \
+            Point it at the `mod_api.json` grug-rs converts TOML, YAML and fragments to",
+            path.display()
+        ));
+    }
+
+    let json = std::fs::read_to_string(path)
+        .map_err(|x| format!("Failed to read `{}`: {x}", path.display()))?;
+
+    serde_json::from_str(&json).map_err(|x| format!("Failed to parse `{}`: {x}", path.display()))
+}
+
+/// Checks the name, arguments and return type of a game function against a parsed mod API
+///
+/// Every mismatched argument and return type is reported, not just the first one.
+fn check_signature(mod_api: &Value, api: &str, sig: &Signature) -> Result<(), Error> {
+    let name = sig.ident.to_string();
+    let Some(game_function) = mod_api["game_functions"].get(&name) else {
+        return Err(Error::new_spanned(
+            &sig.ident,
+            format!("`{name}` is not in the game_functions of `{api}`"),
+        ));
+    };
+
+    let expected: Vec<(&str, &str)> = game_function["arguments"]
+        .as_array()
+        .map(|arguments| {
+            arguments
+                .iter()
+                .map(|argument| {
+                    (
+                        argument["name"].as_str().unwrap_or_default(),
+                        argument["type"].as_str().unwrap_or_default(),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if expected.len() != sig.inputs.len() {
+        return Err(Error::new_spanned(
+            &sig.inputs,
+            format!(
+                "`{name}` takes {} argument(s) in `{api}`, but {} here",
+                expected.len(),
+                sig.inputs.len()
+            ),
+        ));
+    }
+
+    let mut errors: Option<Error> = None;
+    let mut push_error = |error: Error| match &mut errors {
+        Some(errors) => errors.combine(error),
+        None => errors = Some(error),
+    };
+
+    for (arg, (arg_name, grug_type)) in sig.inputs.iter().zip(&expected) {
+        let FnArg::Typed(pattern) = arg else {
+            continue;
+        };
+
+        if !GrugType::parse(grug_type).accepts(&pattern.ty) {
+            push_error(Error::new_spanned(
+                &pattern.ty,
+                format!(
+                    "Argument `{arg_name}` of `{name}` is `{grug_type}` in `{api}`, but `{}` here",
+                    pattern.ty.to_token_stream()
+                ),
+            ));
+        }
+    }
+
+    let return_type = game_function["return_type"].as_str();
+    match (&sig.output, return_type) {
+        (ReturnType::Default, None) => {}
+        (ReturnType::Type(_, ty), Some(grug_type)) if GrugType::parse(grug_type).accepts(ty) => {}
+        (output, return_type) => push_error(Error::new_spanned(
+            output,
+            format!(
+                "`{name}` returns `{}` in `{api}`, but `{}` here",
+                return_type.unwrap_or("nothing"),
+                match output {
+                    ReturnType::Default => "nothing".to_string(),
+                    ReturnType::Type(_, ty) => ty.to_token_stream().to_string(),
+                }
+            ),
+        )),
+    }
+
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(()),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum DetailKind {
+    ResourceExtension,
+    EntityType,
+}
+
+impl DetailKind {
+    fn name(self) -> &'static str {
+        match self {
+            DetailKind::ResourceExtension => "resource_extension",
+            DetailKind::EntityType => "entity_type",
+        }
+    }
+}
+
+/// Like `resource_extension(sprite = ".png")` or `entity_type(target = "Enemy")`
+struct ArgumentDetail {
+    kind: DetailKind,
+    argument: Ident,
+    value: LitStr,
+}
+
+/// Registers a game function's name, arguments and return type for `grug_rs::ModApiBuilder`
+fn register(
+    description: &LitStr,
+    details: &[ArgumentDetail],
+    sig: &Signature,
+) -> Result<proc_macro2::TokenStream, Error> {
+    let name = sig.ident.to_string();
+
+    for detail in details {
+        let is_argument = sig.inputs.iter().any(|arg| {
+            matches!(arg, FnArg::Typed(pattern)
+                if matches!(&*pattern.pat, Pat::Ident(ident) if ident.ident == detail.argument))
+        });
+
+        if !is_argument {
+            return Err(Error::new_spanned(
+                &detail.argument,
+                format!("`{name}` has no argument `{}`", detail.argument),
+            ));
+        }
+    }
+
+    let mut arguments = vec![];
+    for arg in &sig.inputs {
+        let FnArg::Typed(pattern) = arg else {
+            return Err(Error::new_spanned(arg, "Game functions can't take `self`"));
+        };
+
+        let Pat::Ident(ident) = &*pattern.pat else {
+            return Err(Error::new_spanned(
+                &pattern.pat,
+                "Registered game functions need named arguments",
+            ));
+        };
+        let arg_name = ident.ident.to_string();
+        let grug_type =
+            GrugType::from_rust(&pattern.ty).ok_or_else(|| unknown_type(&pattern.ty))?;
+
+        let detail = details.iter().find(|detail| detail.argument == ident.ident);
+        let expected = match grug_type {
+            GrugType::Resource => Some(DetailKind::ResourceExtension),
+            GrugType::Entity => Some(DetailKind::EntityType),
+            _ => None,
+        };
+
+        let detail = match (detail, expected) {
+            (Some(detail), Some(kind)) if detail.kind == kind => detail.value.value(),
+            (Some(detail), _) => {
+                return Err(Error::new_spanned(
+                    &detail.argument,
+                    format!(
+                        "`{}` is for {} arguments, but `{arg_name}` is `{}`",
+                        detail.kind.name(),
+                        match detail.kind {
+                            DetailKind::ResourceExtension => "resource",
+                            DetailKind::EntityType => "entity",
+                        },
+                        grug_type.name()
+                    ),
+                ));
+            }
+            (None, Some(DetailKind::ResourceExtension)) => {
+                return Err(Error::new_spanned(
+                    &pattern.ty,
+                    format!(
+                        "Resource argument `{arg_name}` needs its extension, like `resource_extension({arg_name} = \".png\")`"
+                    ),
+                ));
+            }
+            // Any entity type
+            (None, _) => String::new(),
+        };
+
+        let grug_type = grug_type.name();
+        arguments.push(quote! { (#arg_name, #grug_type, #detail) });
+    }
+
+    let return_type = match &sig.output {
+        ReturnType::Default => quote! { None },
+        ReturnType::Type(_, ty) => {
+            let grug_type = GrugType::from_rust(ty).ok_or_else(|| unknown_type(ty))?;
+            let grug_type = grug_type.name();
+            quote! { Some(#grug_type) }
+        }
+    };
+
+    let private = private_path();
+
+    Ok(quote! {
+        #private::inventory::submit! {
+            #private::RegisteredGameFunction {
+                name: #name,
+                description: #description,
+                arguments: &[#(#arguments),*],
+                return_type: #return_type,
+            }
+        }
+    })
+}
+
+fn unknown_type(ty: &Type) -> Error {
+    Error::new_spanned(ty, format!("`{}` has no grug type", ty.to_token_stream()))
+}
+
+/// Mirrors `grug_rs::mod_api_type::GrugType`, since this crate can't depend on grug-rs
+///
+/// Resources and entities leave out their extension and entity type,
+/// since those don't change how they're passed.
+enum GrugType {
+    Bool,
+    I32,
+    F32,
+    String,
+    Id,
+    Resource,
+    Entity,
+    Custom(String),
+}
+
+impl GrugType {
+    fn parse(name: &str) -> Self {
+        match name {
+            "bool" => GrugType::Bool,
+            "i32" => GrugType::I32,
+            "f32" => GrugType::F32,
+            "string" => GrugType::String,
+            "id" => GrugType::Id,
+            "resource" => GrugType::Resource,
+            "entity" => GrugType::Entity,
+            custom => GrugType::Custom(custom.to_string()),
+        }
+    }
+
+    /// The grug type a Rust type is registered as, custom types are passed by reference
+    fn from_rust(ty: &Type) -> Option<Self> {
+        match ty {
+            Type::Reference(reference) => match &*reference.elem {
+                Type::Path(type_path) => type_path
+                    .path
+                    .segments
+                    .last()
+                    .map(|segment| GrugType::Custom(segment.ident.to_string())),
+                _ => None,
+            },
+            Type::Path(type_path) => match type_path.path.to_token_stream().to_string().as_str() {
+                _ if type_path.path.segments.last().is_some_and(|segment| {
+                    segment.ident == "Resource" || segment.ident == "Asset"
+                }) =>
+                {
+                    Some(GrugType::Resource)
+                }
+                _ if type_path
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "EntityRef") =>
+                {
+                    Some(GrugType::Entity)
+                }
+                "String" => Some(GrugType::String),
+                "i32" => Some(GrugType::I32),
+                "f32" => Some(GrugType::F32),
+                "bool" => Some(GrugType::Bool),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            GrugType::Bool => "bool",
+            GrugType::I32 => "i32",
+            GrugType::F32 => "f32",
+            GrugType::String => "string",
+            GrugType::Id => "id",
+            GrugType::Resource => "resource",
+            GrugType::Entity => "entity",
+            GrugType::Custom(name) => name,
+        }
+    }
+
+    /// Whether grug can pass this type as the Rust type
+    ///
+    /// The names of custom types aren't checked, since they don't have to match the Rust type.
+    fn accepts(&self, ty: &Type) -> bool {
+        matches!(
+            (self, GrugType::from_rust(ty)),
+            (
+                GrugType::String | GrugType::Resource | GrugType::Entity,
+                Some(GrugType::String)
+            ) | (GrugType::Resource, Some(GrugType::Resource))
+                | (GrugType::Entity, Some(GrugType::Entity))
+                | (GrugType::I32, Some(GrugType::I32))
+                | (GrugType::F32, Some(GrugType::F32))
+                | (GrugType::Bool, Some(GrugType::Bool))
+                | (
+                    GrugType::Id | GrugType::Custom(_),
+                    Some(GrugType::Custom(_))
+                )
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn mod_api() -> Value {
+        serde_json::json!({
+            "entities": {},
+            "game_functions": {
+                "print": {
+                    "description": "Prints",
+                    "arguments": [{ "name": "msg", "type": "string" }]
+                },
+                "draw": {
+                    "description": "Draws a sprite on an entity",
+                    "arguments": [
+                        { "name": "sprite", "type": "resource", "resource_extension": ".png" },
+                        { "name": "target", "type": "entity", "entity_type": "" }
+                    ]
+                },
+                "get_health": {
+                    "description": "The health of a target",
+                    "return_type": "f32",
+                    "arguments": [{ "name": "target", "type": "Target" }]
+                }
+            }
+        })
+    }
+
+    fn check(sig: Signature) -> Vec<String> {
+        match check_signature(&mod_api(), "mod_api.json", &sig) {
+            Ok(()) => vec![],
+            Err(errors) => errors.into_iter().map(|error| error.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn matching_signatures() {
+        assert!(check(parse_quote!(fn print(msg: String))).is_empty());
+        assert!(check(parse_quote!(fn draw(sprite: Resource, target: EntityRef))).is_empty());
+        assert!(check(parse_quote!(fn draw(sprite: Asset<Image>, target: String))).is_empty());
+        assert!(check(parse_quote!(fn get_health(target: &Enemy) -> f32)).is_empty());
+    }
+
+    #[test]
+    fn unknown_game_functions() {
+        assert_eq!(
+            check(parse_quote!(fn println(msg: String))),
+            ["`println` is not in the game_functions of `mod_api.json`"]
+        );
+    }
+
+    #[test]
+    fn argument_counts() {
+        assert_eq!(
+            check(parse_quote!(fn print(msg: String, color: i32))),
+            ["`print` takes 1 argument(s) in `mod_api.json`, but 2 here"]
+        );
+    }
+
+    #[test]
+    fn every_mismatch_is_reported() {
+        assert_eq!(
+            check(parse_quote!(fn get_health(target: i32) -> i32)),
+            [
+                "Argument `target` of `get_health` is `Target` in `mod_api.json`, but `i32` here",
+                "`get_health` returns `f32` in `mod_api.json`, but `i32` here",
+            ]
+        );
+        assert_eq!(
+            check(parse_quote!(fn print(msg: String) -> bool)),
+            ["`print` returns `nothing` in `mod_api.json`, but `bool` here"]
+        );
+        assert_eq!(
+            check(parse_quote!(fn get_health(target: &Target))),
+            ["`get_health` returns `f32` in `mod_api.json`, but `nothing` here"]
+        );
+    }
+
+    #[test]
+    fn accepted_rust_types() {
+        let accepts = |grug_type: &str, ty: Type| GrugType::parse(grug_type).accepts(&ty);
+
+        assert!(accepts("string", parse_quote!(String)));
+        assert!(accepts(
+            "resource",
+            parse_quote!(grug_rs::resource::Resource)
+        ));
+        assert!(accepts("entity", parse_quote!(EntityRef)));
+        assert!(accepts("id", parse_quote!(&mut Spawner)));
+        assert!(!accepts("resource", parse_quote!(EntityRef)));
+        assert!(!accepts("i32", parse_quote!(f32)));
+        assert!(!accepts("Target", parse_quote!(String)));
+    }
+
+    #[test]
+    fn unresolved_arguments_return_a_placeholder() {
+        let placeholder = |sig: Signature| resolve_error_return(&sig).unwrap().to_string();
+
+        assert_eq!(
+            placeholder(parse_quote!(fn draw(sprite: Resource))),
+            quote!(return;).to_string()
+        );
+        assert_eq!(
+            placeholder(parse_quote!(fn health(target: EntityRef) -> f32)),
+            quote!(return Default::default();).to_string()
+        );
+        assert_eq!(
+            placeholder(parse_quote!(fn name(target: EntityRef) -> *const c_char)),
+            quote!(return std::ptr::null();).to_string()
+        );
+    }
+
+    #[test]
+    fn unresolved_arguments_need_a_placeholder() {
+        for sig in [
+            parse_quote!(fn spawner(target: EntityRef) -> &mut Spawner),
+            parse_quote!(fn size(sprite: Asset<Image>) -> Vec2),
+        ] {
+            let error = resolve_error_return(&sig).unwrap_err().to_string();
+
+            assert!(
+                error.contains("so it can only return nothing, a number, a `bool` or a pointer"),
+                "{error}"
+            );
+        }
+    }
+
+    #[test]
+    fn only_json_is_read() {
+        for path in ["mod_api.toml", "mod_api.yaml", "src"] {
+            let error = read_mod_api(Path::new(path)).unwrap_err();
+
+            assert!(
+                error.starts_with(&format!(
+                    "Only JSON is supported by `api =`, but `{path}` isn't a `.json` file"
+                )),
+                "{error}"
+            );
+        }
+
+        assert!(
+            read_mod_api(Path::new("does_not_exist.json"))
+                .unwrap_err()
+                .starts_with("Failed to read `does_not_exist.json`")
+        );
+    }
+}
@@ -0,0 +1,44 @@
+//! Compares two mod APIs, to check whether mods made for the old one still work with the new one
+//!
+//! Usage: `mod_api_diff <old mod_api.json> <new mod_api.json>`
+//!
+//! Prints every change, and exits with 1 if any of them is breaking or 2 if a mod API can't be loaded.
+
+use std::process::ExitCode;
+
+use grug_rs::mod_api_type::ModAPI;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [old_path, new_path] = args.as_slice() else {
+        eprintln!("Usage: mod_api_diff <old mod_api.json> <new mod_api.json>");
+        return ExitCode::from(2);
+    };
+
+    let (old, new) = match (ModAPI::load(old_path), ModAPI::load(new_path)) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(error), _) | (_, Err(error)) => {
+            eprintln!("{error}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let changes = ModAPI::diff(&old, &new);
+    for change in &changes {
+        let label = if change.breaking {
+            "breaking"
+        } else {
+            "compatible"
+        };
+        println!("{label}: {change}");
+    }
+
+    let breaking = changes.iter().filter(|change| change.breaking).count();
+    println!("{} change(s), {breaking} of them breaking", changes.len());
+
+    if breaking > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,25 @@
+//! Writes reference documentation of a mod API for modders
+//!
+//! Usage: `mod_api_docs <mod_api.json> <out dir>`
+//!
+//! Writes `mod_api.md` and `mod_api.html` into the out dir, and exits with 2 if that fails.
+
+use std::process::ExitCode;
+
+use grug_rs::{docs, mod_api_type::ModAPI};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [mod_api_path, out_dir] = args.as_slice() else {
+        eprintln!("Usage: mod_api_docs <mod_api.json> <out dir>");
+        return ExitCode::from(2);
+    };
+
+    match ModAPI::load(mod_api_path).and_then(|mod_api| docs::generate(&mod_api, out_dir)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::from(2)
+        }
+    }
+}
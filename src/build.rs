@@ -0,0 +1,402 @@
+//! Generates typed bindings from a `mod_api.json` in your `build.rs`
+//!
+//! Every entity becomes a struct with a method per `on_function`,
+//! so renaming something in `mod_api.json` becomes a compile error in your game.
+//!
+//! # Example
+//! In `Cargo.toml`, without the `runtime` feature so grug itself isn't built for the build script:
+//! ```toml
+//! [build-dependencies]
+//! grug-rs = { version = "0.3", default-features = false }
+//! ```
+//!
+//! In `build.rs`:
+//! ```rs
+//! fn main() {
+//!     grug_rs::build::generate("mod_api.json").unwrap();
+//!     println!("cargo:rustc-link-arg=-rdynamic");
+//! }
+//! ```
+//!
+//! In your game:
+//! ```rs
+//! mod mod_api {
+//!     include!(concat!(env!("OUT_DIR"), "/mod_api.rs"));
+//! }
+//!
+//! mod_api::World::new(&grug).on_update()?;
+//! ```
+//!
+//! The bindings refer to grug-rs as `grug_rs`, so if your game renames it,
+//! like `grug = { package = "grug-rs" }`, use `generate_renamed("mod_api.json", "grug")`.
+
+use std::{
+    collections::HashMap,
+    env,
+    fmt::Write,
+    fs::write,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    GrugError,
+    mod_api_type::{Argument, Entity, GrugType, ModAPI},
+};
+
+/// Writes the bindings of a `mod_api.json` to `$OUT_DIR/<file stem>.rs`
+///
+/// Tells cargo to rerun the build script when the `mod_api.json` changes.
+pub fn generate<P: AsRef<Path>>(mod_api_path: P) -> Result<PathBuf, GrugError> {
+    generate_renamed(mod_api_path, "grug_rs")
+}
+
+/// Like `generate`, with the bindings referring to grug-rs by the name your game uses
+pub fn generate_renamed<P: AsRef<Path>>(
+    mod_api_path: P,
+    crate_name: &str,
+) -> Result<PathBuf, GrugError> {
+    let mod_api_path = mod_api_path.as_ref();
+    println!("cargo:rerun-if-changed={}", mod_api_path.display());
+
+    let mod_api = ModAPI::load(mod_api_path)?;
+
+    let file_name = mod_api_path
+        .file_stem()
+        .map_or("mod_api".into(), |stem| stem.to_string_lossy());
+    let out_dir = env::var_os("OUT_DIR").ok_or_else(|| GrugError::WriteBindings {
+        path: PathBuf::from(format!("{file_name}.rs")),
+        error: "`OUT_DIR` isn't set, call this from a build script".to_string(),
+    })?;
+    let out_path = Path::new(&out_dir).join(format!("{file_name}.rs"));
+
+    write(&out_path, bindings(&mod_api, crate_name)?).map_err(|x| GrugError::WriteBindings {
+        path: out_path.clone(),
+        error: x.to_string(),
+    })?;
+
+    Ok(out_path)
+}
+
+/// The Rust source of the bindings of a mod API, referring to grug-rs as `crate_name`
+///
+/// Fails if names turn into the same Rust identifier, like the entities `foo_bar` and `FooBar`.
+pub fn bindings(mod_api: &ModAPI, crate_name: &str) -> Result<String, GrugError> {
+    let mut duplicates = vec![];
+
+    find_duplicates(
+        &mut duplicates,
+        "entities",
+        mod_api.entities.keys(),
+        type_name,
+    );
+    for (name, entity) in &mod_api.entities {
+        find_duplicates(
+            &mut duplicates,
+            &format!("on_functions of `{name}`"),
+            entity.on_functions.keys(),
+            value_name,
+        );
+
+        for (on_function, data) in &entity.on_functions {
+            find_duplicates(
+                &mut duplicates,
+                &format!("arguments of `{name}.{on_function}`"),
+                data.arguments.iter().map(|argument| &argument.name),
+                value_name,
+            );
+        }
+    }
+
+    if !duplicates.is_empty() {
+        return Err(GrugError::DuplicateIdentifiers { duplicates });
+    }
+
+    let mut out = String::from("// Generated by `grug_rs::build`, don't edit\n");
+
+    for (name, entity) in &mod_api.entities {
+        entity_bindings(&mut out, crate_name, name, entity);
+    }
+
+    Ok(out)
+}
+
+/// Adds a message like ``entities `foo_bar` and `FooBar` are both `FooBar` `` for every clash
+fn find_duplicates<'a>(
+    duplicates: &mut Vec<String>,
+    what: &str,
+    names: impl Iterator<Item = &'a String>,
+    identifier: fn(&str) -> String,
+) {
+    let mut seen: HashMap<String, &str> = HashMap::new();
+
+    for name in names {
+        let identifier = identifier(name);
+
+        if let Some(first) = seen.get(&identifier) {
+            duplicates.push(format!(
+                "{what} `{first}` and `{name}` are both `{identifier}`"
+            ));
+        } else {
+            seen.insert(identifier, name);
+        }
+    }
+}
+
+fn entity_bindings(out: &mut String, crate_name: &str, name: &str, entity: &Entity) {
+    let struct_name = type_name(name);
+
+    writeln!(out).unwrap();
+    writeln!(out, "#[doc = {:?}]", entity.description).unwrap();
+    writeln!(out, "#[derive(Clone, Copy)]").unwrap();
+    writeln!(out, "pub struct {struct_name}<'grug> {{").unwrap();
+    writeln!(out, "    grug: &'grug ::{crate_name}::Grug,").unwrap();
+    writeln!(out, "}}").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "impl<'grug> {struct_name}<'grug> {{").unwrap();
+    writeln!(out, "    pub const ENTITY_TYPE: &'static str = {name:?};").unwrap();
+    writeln!(out).unwrap();
+    writeln!(
+        out,
+        "    pub fn new(grug: &'grug ::{crate_name}::Grug) -> Self {{"
+    )
+    .unwrap();
+    writeln!(out, "        Self {{ grug }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+
+    for (on_function, data) in &entity.on_functions {
+        let parameters: String = data
+            .arguments
+            .iter()
+            .map(|argument| {
+                format!(
+                    ", {}: {}",
+                    value_name(&argument.name),
+                    rust_type(crate_name, &argument.type_)
+                )
+            })
+            .collect();
+        let values: Vec<String> = data
+            .arguments
+            .iter()
+            .map(|argument| grug_value(crate_name, argument))
+            .collect();
+
+        writeln!(out).unwrap();
+        writeln!(out, "    #[doc = {:?}]", data.description).unwrap();
+        writeln!(
+            out,
+            "    pub fn {}(&self{parameters}) -> Result<(), ::{crate_name}::GrugError> {{",
+            value_name(on_function)
+        )
+        .unwrap();
+        writeln!(out, "        self.grug.activate_on_function(").unwrap();
+        writeln!(out, "            {name:?},").unwrap();
+        writeln!(out, "            {on_function:?},").unwrap();
+        writeln!(
+            out,
+            "            &mut ::{crate_name}::Arguments::new(vec![{}]),",
+            values.join(", ")
+        )
+        .unwrap();
+        writeln!(out, "        )").unwrap();
+        writeln!(out, "    }}").unwrap();
+    }
+
+    writeln!(out, "}}").unwrap();
+}
+
+/// The Rust type of a grug type, custom types are passed as a `GrugValue`
+fn rust_type(crate_name: &str, type_: &GrugType) -> String {
+    match type_ {
+        GrugType::String | GrugType::Resource { .. } | GrugType::Entity { .. } => {
+            "String".to_string()
+        }
+        GrugType::I32 => "i32".to_string(),
+        GrugType::F32 => "f32".to_string(),
+        GrugType::Bool => "bool".to_string(),
+        GrugType::Id | GrugType::Custom(_) => format!("::{crate_name}::GrugValue<'_>"),
+    }
+}
+
+fn grug_value(crate_name: &str, argument: &Argument) -> String {
+    let name = value_name(&argument.name);
+
+    match argument.type_ {
+        GrugType::String | GrugType::Resource { .. } | GrugType::Entity { .. } => {
+            format!("::{crate_name}::GrugValue::String({name})")
+        }
+        GrugType::I32 => format!("::{crate_name}::GrugValue::I32({name})"),
+        GrugType::F32 => format!("::{crate_name}::GrugValue::F32({name})"),
+        GrugType::Bool => format!("::{crate_name}::GrugValue::Bool({name})"),
+        GrugType::Id | GrugType::Custom(_) => name,
+    }
+}
+
+/// Turns `world` or `player_ship` into `World` or `PlayerShip`
+fn type_name(name: &str) -> String {
+    let name: String = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect();
+
+    identifier(name)
+}
+
+/// Turns a name into a valid function or argument name
+fn value_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    match name.as_str() {
+        // These can't be raw identifiers
+        "self" | "Self" | "super" | "crate" => format!("{name}_"),
+        _ => identifier(name),
+    }
+}
+
+fn identifier(name: String) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern",
+        "false", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "static", "struct", "trait", "true", "type", "unsafe",
+        "use", "where", "while",
+    ];
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else if KEYWORDS.contains(&name.as_str()) {
+        format!("r#{name}")
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mod_api(json: &str) -> ModAPI {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn bindings_of_an_entity() {
+        let mod_api = mod_api(
+            r#"{
+                "entities": {
+                    "player_ship": {
+                        "description": "The ship you fly",
+                        "on_functions": {
+                            "on_hit": {
+                                "description": "Called when hit",
+                                "arguments": [
+                                    { "name": "damage", "type": "i32" },
+                                    { "name": "type", "type": "string" }
+                                ]
+                            }
+                        }
+                    }
+                },
+                "game_functions": {}
+            }"#,
+        );
+        let bindings = bindings(&mod_api, "grug_rs").unwrap();
+
+        assert!(bindings.contains("pub struct PlayerShip<'grug> {"));
+        assert!(bindings.contains("pub const ENTITY_TYPE: &'static str = \"player_ship\";"));
+        assert!(bindings.contains(
+            "pub fn on_hit(&self, damage: i32, r#type: String) -> Result<(), ::grug_rs::GrugError> {"
+        ));
+        assert!(bindings.contains(
+            "&mut ::grug_rs::Arguments::new(vec![::grug_rs::GrugValue::I32(damage), ::grug_rs::GrugValue::String(r#type)]),"
+        ));
+        assert!(bindings.contains("#[doc = \"Called when hit\"]"));
+    }
+
+    #[test]
+    fn type_names() {
+        assert_eq!(type_name("World"), "World");
+        assert_eq!(type_name("player_ship"), "PlayerShip");
+        assert_eq!(type_name("big-boss"), "BigBoss");
+        assert_eq!(type_name("2d_sprite"), "_2dSprite");
+        assert_eq!(type_name(""), "_");
+    }
+
+    #[test]
+    fn value_names() {
+        assert_eq!(value_name("on_update"), "on_update");
+        assert_eq!(value_name("on-hit"), "on_hit");
+        assert_eq!(value_name("type"), "r#type");
+        assert_eq!(value_name("self"), "self_");
+        assert_eq!(value_name("1st"), "_1st");
+    }
+
+    #[test]
+    fn custom_types_are_passed_as_grug_values() {
+        let argument = Argument {
+            name: "target".to_string(),
+            type_: GrugType::Custom("Target".to_string()),
+        };
+
+        assert_eq!(rust_type("grug", &argument.type_), "::grug::GrugValue<'_>");
+        assert_eq!(grug_value("grug", &argument), "target");
+    }
+
+    #[test]
+    fn duplicate_identifiers() {
+        let mod_api = mod_api(
+            r#"{
+                "entities": {
+                    "foo_bar": {
+                        "description": "",
+                        "on_functions": {
+                            "on_hit": {
+                                "description": "",
+                                "arguments": [
+                                    { "name": "self", "type": "i32" },
+                                    { "name": "self_", "type": "i32" }
+                                ]
+                            },
+                            "on-hit": { "description": "" }
+                        }
+                    },
+                    "FooBar": { "description": "", "on_functions": {} }
+                },
+                "game_functions": {}
+            }"#,
+        );
+        let error = bindings(&mod_api, "grug_rs").unwrap_err();
+
+        let GrugError::DuplicateIdentifiers { duplicates } = error else {
+            panic!("{error}");
+        };
+        assert_eq!(
+            duplicates,
+            [
+                "entities `foo_bar` and `FooBar` are both `FooBar`",
+                "on_functions of `foo_bar` `on_hit` and `on-hit` are both `on_hit`",
+                "arguments of `foo_bar.on_hit` `self` and `self_` are both `self_`",
+            ]
+        );
+    }
+
+    #[test]
+    fn example_bindings_are_up_to_date() {
+        let mod_api = ModAPI::load("examples/typed_bindings/mod_api.json").unwrap();
+
+        assert_eq!(
+            bindings(&mod_api, "grug").unwrap(),
+            include_str!("../examples/typed_bindings/bindings.rs"),
+            "regenerate examples/typed_bindings/bindings.rs"
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use crate::grug_value::OwnedGrugValue;
+
+/// A command that mutates the world, deferred until the current activation has ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Spawn an entity, e.g. `"base_game:goblin"`.
+    /// Handled by the host in `Grug::flush_commands`.
+    Spawn { entity: String },
+    /// Despawn an entity by its id.
+    /// Handled by the host in `Grug::flush_commands`.
+    Despawn { id: u64 },
+    /// Activate an `on_function` on every file of an entity type.
+    /// Handled by grug-rs in `Grug::flush_commands`.
+    Activate {
+        entity_type: String,
+        on_function: String,
+        arguments: Vec<OwnedGrugValue>,
+    },
+    /// Activate an `on_function` on a single entity, e.g. `"base_game:goblin"`.
+    /// Handled by grug-rs in `Grug::flush_commands`.
+    ActivateEntity {
+        entity: String,
+        on_function: String,
+        arguments: Vec<OwnedGrugValue>,
+    },
+}
+
+/// A first in, first out queue of deferred commands
+///
+/// Cloning a `CommandQueue` gives another handle to the same queue,
+/// so a handle can be stored wherever your game functions can reach it.
+///
+/// # Example
+/// ```no_run
+/// use std::sync::OnceLock;
+///
+/// use grug_rs::{Command, CommandQueue, Grug};
+///
+/// static COMMANDS: OnceLock<CommandQueue> = OnceLock::new();
+///
+/// # fn main() -> Result<(), grug_rs::GrugError> {
+/// let grug: Grug = todo!();
+/// COMMANDS.set(grug.commands()).unwrap();
+///
+/// // Inside of a game function
+/// COMMANDS.get().unwrap().push(Command::Spawn {
+///     entity: "base_game:goblin".to_string(),
+/// });
+///
+/// // After the activation has ended
+/// grug.flush_commands(|command| println!("{command:?}"))?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CommandQueue {
+    commands: Arc<Mutex<VecDeque<Command>>>,
+}
+
+impl CommandQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pushes a command to the back of the queue
+    pub fn push(&self, command: Command) {
+        self.commands.lock().unwrap().push_back(command);
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.lock().unwrap().is_empty()
+    }
+
+    /// Takes every command currently in the queue, leaving it empty
+    pub(crate) fn take(&self) -> VecDeque<Command> {
+        std::mem::take(&mut *self.commands.lock().unwrap())
+    }
+
+    /// Puts commands back at the front of the queue, keeping their order
+    pub(crate) fn requeue(&self, mut commands: VecDeque<Command>) {
+        let mut queue = self.commands.lock().unwrap();
+        commands.append(&mut queue);
+        *queue = commands;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn despawn(id: u64) -> Command {
+        Command::Despawn { id }
+    }
+
+    #[test]
+    fn first_in_first_out() {
+        let queue = CommandQueue::new();
+        queue.push(despawn(1));
+        queue.push(despawn(2));
+        queue.push(despawn(3));
+
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.take(), [despawn(1), despawn(2), despawn(3)]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn clones_share_the_queue() {
+        let queue = CommandQueue::new();
+        queue.clone().push(despawn(1));
+
+        assert_eq!(queue.take(), [despawn(1)]);
+    }
+
+    #[test]
+    fn pushed_while_flushing_are_left_for_the_next_flush() {
+        let queue = CommandQueue::new();
+        queue.push(despawn(1));
+
+        let mut flushed = queue.take();
+        // Like a game function run by an `Activate` command
+        queue.push(despawn(2));
+
+        assert_eq!(flushed.pop_front(), Some(despawn(1)));
+        assert!(flushed.is_empty());
+        assert_eq!(queue.take(), [despawn(2)]);
+    }
+
+    #[test]
+    fn requeue_keeps_unapplied_commands_in_front() {
+        let queue = CommandQueue::new();
+        queue.push(despawn(1));
+        queue.push(despawn(2));
+        queue.push(despawn(3));
+
+        let mut flushed = queue.take();
+        flushed.pop_front();
+        // Pushed while flushing, before the flush failed
+        queue.push(despawn(4));
+        queue.requeue(flushed);
+
+        assert_eq!(queue.take(), [despawn(2), despawn(3), despawn(4)]);
+    }
+}
@@ -0,0 +1,391 @@
+//! Generates reference documentation of a mod API for modders, as Markdown and HTML
+//!
+//! Anchors only depend on names, like `#entity-World`, `#entity-World-on_update`
+//! and `#game_function-println`, so links to them keep working across versions.
+//!
+//! # Example
+//! ```no_run
+//! use grug_rs::{docs, mod_api_type::ModAPI};
+//!
+//! # fn main() -> Result<(), grug_rs::GrugError> {
+//! // Writes `docs/mod_api.md` and `docs/mod_api.html`
+//! docs::generate(&ModAPI::load("./mod_api.json")?, "./docs")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    fmt::Write,
+    fs::{create_dir_all, write},
+    path::Path,
+};
+
+use crate::{
+    GrugError,
+    mod_api_type::{Argument, GameFunction, GrugType, ModAPI},
+};
+
+/// Writes `mod_api.md` and `mod_api.html` into `out_dir`, creating it if needed
+pub fn generate<P: AsRef<Path>>(mod_api: &ModAPI, out_dir: P) -> Result<(), GrugError> {
+    let out_dir = out_dir.as_ref();
+
+    create_dir_all(out_dir).map_err(|x| GrugError::WriteDocs {
+        path: out_dir.to_path_buf(),
+        error: x.to_string(),
+    })?;
+
+    for (file_name, docs) in [
+        ("mod_api.md", markdown(mod_api)),
+        ("mod_api.html", html(mod_api)),
+    ] {
+        let path = out_dir.join(file_name);
+
+        write(&path, docs).map_err(|x| GrugError::WriteDocs {
+            path,
+            error: x.to_string(),
+        })?;
+    }
+
+    Ok(())
+}
+
+/// The documentation of a mod API as Markdown
+pub fn markdown(mod_api: &ModAPI) -> String {
+    let mut out = String::from("# Mod API\n");
+
+    writeln!(out, "\n## Entities").unwrap();
+    for (name, entity) in &mod_api.entities {
+        writeln!(out, "\n<a id=\"{}\"></a>\n", entity_anchor(name)).unwrap();
+        writeln!(out, "### {name}\n").unwrap();
+        writeln!(out, "{}", entity.description).unwrap();
+
+        let used_by = used_by(mod_api, name);
+        if !used_by.is_empty() {
+            let links: Vec<String> = used_by
+                .iter()
+                .map(|name| format!("[`{name}`](#{})", game_function_anchor(name)))
+                .collect();
+            writeln!(out, "\nGiven to: {}", links.join(", ")).unwrap();
+        }
+
+        for (on_function, data) in &entity.on_functions {
+            writeln!(
+                out,
+                "\n<a id=\"{}\"></a>\n",
+                on_function_anchor(name, on_function)
+            )
+            .unwrap();
+            markdown_function(&mut out, "####", on_function, data);
+        }
+    }
+
+    writeln!(out, "\n## Game functions").unwrap();
+    for (name, game_function) in &mod_api.game_functions {
+        writeln!(out, "\n<a id=\"{}\"></a>\n", game_function_anchor(name)).unwrap();
+        markdown_function(&mut out, "###", name, game_function);
+    }
+
+    out
+}
+
+fn markdown_function(out: &mut String, heading: &str, name: &str, function: &GameFunction) {
+    writeln!(out, "{heading} `{}`\n", signature(name, function)).unwrap();
+    writeln!(out, "{}", function.description).unwrap();
+
+    if !function.arguments.is_empty() {
+        writeln!(out, "\n| Argument | Type |").unwrap();
+        writeln!(out, "| --- | --- |").unwrap();
+
+        for argument in &function.arguments {
+            let type_ = match &argument.type_ {
+                GrugType::Entity { entity_type } if !entity_type.is_empty() => format!(
+                    "`entity` of [{entity_type}](#{})",
+                    entity_anchor(entity_type)
+                ),
+                GrugType::Resource { extension } if !extension.is_empty() => {
+                    format!("`resource` ending in `{extension}`")
+                }
+                type_ => format!("`{type_}`"),
+            };
+
+            writeln!(out, "| `{}` | {type_} |", argument.name).unwrap();
+        }
+    }
+
+    if let Some(return_type) = &function.return_type {
+        writeln!(out, "\nReturns `{return_type}`").unwrap();
+    }
+}
+
+/// The documentation of a mod API as a standalone HTML page
+pub fn html(mod_api: &ModAPI) -> String {
+    let mut out = String::new();
+
+    writeln!(out, "<!DOCTYPE html>").unwrap();
+    writeln!(out, "<html>").unwrap();
+    writeln!(out, "<head>").unwrap();
+    writeln!(out, "<meta charset=\"utf-8\">").unwrap();
+    writeln!(out, "<title>Mod API</title>").unwrap();
+    writeln!(
+        out,
+        "<style>body {{ font-family: sans-serif; max-width: 50em; margin: auto; }} table {{ border-collapse: collapse; }} td, th {{ border: 1px solid #ccc; padding: 0.25em 0.5em; }}</style>"
+    )
+    .unwrap();
+    writeln!(out, "</head>").unwrap();
+    writeln!(out, "<body>").unwrap();
+    writeln!(out, "<h1>Mod API</h1>").unwrap();
+
+    writeln!(out, "<h2>Entities</h2>").unwrap();
+    for (name, entity) in &mod_api.entities {
+        writeln!(
+            out,
+            "<h3 id=\"{}\">{}</h3>",
+            entity_anchor(name),
+            escape(name)
+        )
+        .unwrap();
+        writeln!(out, "<p>{}</p>", escape(&entity.description)).unwrap();
+
+        let used_by = used_by(mod_api, name);
+        if !used_by.is_empty() {
+            let links: Vec<String> = used_by
+                .iter()
+                .map(|name| {
+                    format!(
+                        "<a href=\"#{}\"><code>{}</code></a>",
+                        game_function_anchor(name),
+                        escape(name)
+                    )
+                })
+                .collect();
+            writeln!(out, "<p>Given to: {}</p>", links.join(", ")).unwrap();
+        }
+
+        for (on_function, data) in &entity.on_functions {
+            html_function(
+                &mut out,
+                "h4",
+                &on_function_anchor(name, on_function),
+                on_function,
+                data,
+            );
+        }
+    }
+
+    writeln!(out, "<h2>Game functions</h2>").unwrap();
+    for (name, game_function) in &mod_api.game_functions {
+        html_function(
+            &mut out,
+            "h3",
+            &game_function_anchor(name),
+            name,
+            game_function,
+        );
+    }
+
+    writeln!(out, "</body>").unwrap();
+    writeln!(out, "</html>").unwrap();
+
+    out
+}
+
+fn html_function(
+    out: &mut String,
+    heading: &str,
+    anchor: &str,
+    name: &str,
+    function: &GameFunction,
+) {
+    writeln!(
+        out,
+        "<{heading} id=\"{anchor}\"><code>{}</code></{heading}>",
+        escape(&signature(name, function))
+    )
+    .unwrap();
+    writeln!(out, "<p>{}</p>", escape(&function.description)).unwrap();
+
+    if !function.arguments.is_empty() {
+        writeln!(out, "<table>").unwrap();
+        writeln!(out, "<tr><th>Argument</th><th>Type</th></tr>").unwrap();
+
+        for argument in &function.arguments {
+            let type_ = match &argument.type_ {
+                GrugType::Entity { entity_type } if !entity_type.is_empty() => format!(
+                    "<code>entity</code> of <a href=\"#{}\">{}</a>",
+                    entity_anchor(entity_type),
+                    escape(entity_type)
+                ),
+                GrugType::Resource { extension } if !extension.is_empty() => format!(
+                    "<code>resource</code> ending in <code>{}</code>",
+                    escape(extension)
+                ),
+                type_ => format!("<code>{}</code>", escape(type_.name())),
+            };
+
+            writeln!(
+                out,
+                "<tr><td><code>{}</code></td><td>{type_}</td></tr>",
+                escape(&argument.name)
+            )
+            .unwrap();
+        }
+
+        writeln!(out, "</table>").unwrap();
+    }
+
+    if let Some(return_type) = &function.return_type {
+        writeln!(
+            out,
+            "<p>Returns <code>{}</code></p>",
+            escape(return_type.name())
+        )
+        .unwrap();
+    }
+}
+
+/// Like `println(msg: string)` or `add(a: i32, b: i32) -> i32`
+fn signature(name: &str, function: &GameFunction) -> String {
+    let arguments: Vec<String> = function
+        .arguments
+        .iter()
+        .map(|Argument { name, type_ }| format!("{name}: {type_}"))
+        .collect();
+
+    match &function.return_type {
+        Some(return_type) => format!("{name}({}) -> {return_type}", arguments.join(", ")),
+        None => format!("{name}({})", arguments.join(", ")),
+    }
+}
+
+/// The game functions with an argument of the entity type
+fn used_by<'a>(mod_api: &'a ModAPI, entity_type: &str) -> Vec<&'a str> {
+    mod_api
+        .game_functions
+        .iter()
+        .filter(|(_, game_function)| {
+            game_function.arguments.iter().any(|argument| {
+                matches!(&argument.type_, GrugType::Entity { entity_type: other } if other == entity_type)
+            })
+        })
+        .map(|(name, _)| name.as_str())
+        .collect()
+}
+
+fn entity_anchor(name: &str) -> String {
+    format!("entity-{}", anchor_name(name))
+}
+
+fn on_function_anchor(entity: &str, on_function: &str) -> String {
+    format!(
+        "entity-{}-{}",
+        anchor_name(entity),
+        anchor_name(on_function)
+    )
+}
+
+fn game_function_anchor(name: &str) -> String {
+    format!("game_function-{}", anchor_name(name))
+}
+
+/// Keeps the valid grug names the mod API should have, and replaces anything else
+fn anchor_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mod_api() -> ModAPI {
+        ModAPI::builder()
+            .entity("World", "The <whole> world")
+            .on_function("on_update", GameFunction::new("Called every tick"))
+            .entity("Enemy", "Fights the player")
+            .game_function(
+                "despawn",
+                GameFunction::new("Despawns an enemy").argument(
+                    "enemy",
+                    GrugType::Entity {
+                        entity_type: "Enemy".to_string(),
+                    },
+                ),
+            )
+            .game_function(
+                "add",
+                GameFunction::new("Adds")
+                    .argument("a", "i32")
+                    .argument("b", "i32")
+                    .return_type("i32"),
+            )
+            .build()
+    }
+
+    #[test]
+    fn anchors() {
+        assert_eq!(entity_anchor("World"), "entity-World");
+        assert_eq!(
+            on_function_anchor("World", "on_update"),
+            "entity-World-on_update"
+        );
+        assert_eq!(game_function_anchor("println"), "game_function-println");
+        assert_eq!(anchor_name("big boss<3>"), "big-boss-3-");
+    }
+
+    #[test]
+    fn markdown_links_to_anchors() {
+        let markdown = markdown(&mod_api());
+
+        assert!(markdown.contains("<a id=\"entity-World\"></a>\n\n### World\n"));
+        assert!(markdown.contains(
+            "<a id=\"entity-World-on_update\"></a>\n\n#### `on_update()`\n\nCalled every tick\n"
+        ));
+        assert!(markdown.contains("Given to: [`despawn`](#game_function-despawn)"));
+        assert!(markdown.contains("| `enemy` | `entity` of [Enemy](#entity-Enemy) |"));
+        assert!(markdown.contains("### `add(a: i32, b: i32) -> i32`"));
+        assert!(markdown.contains("\nReturns `i32`\n"));
+    }
+
+    #[test]
+    fn html_escapes_and_has_the_same_anchors() {
+        let html = html(&mod_api());
+
+        assert!(html.contains("<h3 id=\"entity-World\">World</h3>"));
+        assert!(html.contains("<p>The &lt;whole&gt; world</p>"));
+        assert!(html.contains("<h4 id=\"entity-World-on_update\"><code>on_update()</code></h4>"));
+        assert!(html.contains(
+            "<h3 id=\"game_function-add\"><code>add(a: i32, b: i32) -&gt; i32</code></h3>"
+        ));
+        assert!(html.contains("<a href=\"#entity-Enemy\">Enemy</a>"));
+    }
+
+    #[test]
+    fn used_by_only_lists_game_functions_taking_the_entity() {
+        let mod_api = mod_api();
+
+        assert_eq!(used_by(&mod_api, "Enemy"), ["despawn"]);
+        assert!(used_by(&mod_api, "World").is_empty());
+    }
+
+    #[test]
+    fn escape_html() {
+        assert_eq!(
+            escape(r#"<a href="x">&</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use std::ffi::{CStr, c_char};
+
+use crate::{
+    FileCache, GrugFile,
+    mod_api_type::{GrugType, ModAPI},
+    with_running_grug,
+};
+
+/// A loaded entity, given to a game function with an `entity` argument
+///
+/// It's checked against the active mods and the `entity_type` of the argument,
+/// so a mod passing an entity that doesn't exist gets a game function error.
+///
+/// # Example
+/// ```no_run
+/// # #![deny(improper_ctypes_definitions)]
+/// use grug_rs::EntityRef;
+/// use grug_rs_proc_macro::game_function;
+///
+/// #[game_function]
+/// fn spawn(enemy: EntityRef) {
+///     println!("Spawning {} from {}", enemy.entity(), enemy.file().relative_path().display());
+/// }
+/// ```
+#[derive(Clone)]
+pub struct EntityRef {
+    file: GrugFile,
+}
+
+impl EntityRef {
+    /// Resolves the entity grug passes to a game function,
+    /// checking it against the `entity_type` of the argument
+    ///
+    /// Used by `#[game_function]`.
+    ///
+    /// # Safety
+    /// `raw` has to be a valid C string
+    #[doc(hidden)]
+    pub unsafe fn from_game_function(
+        raw: *const c_char,
+        game_function: &str,
+        argument: usize,
+    ) -> Result<Self, String> {
+        if raw.is_null() {
+            return Err(format!("`{game_function}` was given a null entity"));
+        }
+
+        let entity = unsafe { CStr::from_ptr(raw) }.to_string_lossy().to_string();
+
+        with_running_grug(|grug| {
+            Self::resolve(
+                entity.clone(),
+                &grug.file_cache(),
+                &grug.mod_api,
+                game_function,
+                argument,
+            )
+        })
+        .unwrap_or_else(|| {
+            Err(format!(
+                "`{game_function}` was given the entity `{entity}` outside of an on_function"
+            ))
+        })
+    }
+
+    /// Looks the entity up in the active files,
+    /// and checks it against the `entity_type` of the argument in the mod API
+    fn resolve(
+        entity: String,
+        files: &FileCache,
+        mod_api: &ModAPI,
+        game_function: &str,
+        argument: usize,
+    ) -> Result<Self, String> {
+        let file = files
+            .file_by_entity(entity.clone())
+            .map_err(|x| x.to_string())?;
+
+        let expected = mod_api
+            .game_functions
+            .get(game_function)
+            .and_then(|game_function| game_function.arguments.get(argument))
+            .map(|argument| &argument.type_);
+
+        if let Some(GrugType::Entity { entity_type }) = expected
+            && !entity_type.is_empty()
+            && file.entity_type() != entity_type
+        {
+            return Err(format!(
+                "The entity `{entity}` given to `{game_function}` is a `{}`, but it needs to be a `{entity_type}`",
+                file.entity_type()
+            ));
+        }
+
+        Ok(Self { file })
+    }
+
+    /// The entity including its mod, like `base_game:goblin`
+    pub fn entity(&self) -> &str {
+        self.file.entity()
+    }
+
+    /// The entity type, like `Enemy`
+    pub fn entity_type(&self) -> &str {
+        self.file.entity_type()
+    }
+
+    /// The grug file defining the entity
+    pub fn file(&self) -> &GrugFile {
+        &self.file
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, path::Path, ptr::null};
+
+    use grug_sys::grug_file;
+
+    use super::*;
+    use crate::mod_api_type::GameFunction;
+
+    fn file(name: &'static CStr, entity: &'static CStr, entity_type: &'static CStr) -> GrugFile {
+        let mut file: grug_file = unsafe { std::mem::zeroed() };
+        file.name = name.as_ptr();
+        file.entity = entity.as_ptr();
+        file.entity_type = entity_type.as_ptr();
+        unsafe { GrugFile::in_dir(file, Path::new("animals"), &ModAPI::default()) }
+    }
+
+    fn files() -> FileCache {
+        let active = vec![
+            file(c"cow-Animal.grug", c"animals:cow", c"Animal"),
+            file(c"tractor-Vehicle.grug", c"animals:tractor", c"Vehicle"),
+        ];
+        let by_entity = active
+            .iter()
+            .enumerate()
+            .map(|(i, file)| (file.entity().to_string(), i))
+            .collect();
+
+        FileCache {
+            generation: 0,
+            running: (0..active.len()).collect(),
+            active,
+            by_entity_type: HashMap::new(),
+            by_entity,
+            conflicts: vec![],
+        }
+    }
+
+    fn mod_api() -> ModAPI {
+        ModAPI::builder()
+            .game_function(
+                "pet",
+                GameFunction::new("Pets an animal").argument(
+                    "animal",
+                    GrugType::Entity {
+                        entity_type: "Animal".to_string(),
+                    },
+                ),
+            )
+            .game_function(
+                "look_at",
+                GameFunction::new("Looks at anything").argument(
+                    "thing",
+                    GrugType::Entity {
+                        entity_type: String::new(),
+                    },
+                ),
+            )
+            .build()
+    }
+
+    fn resolve(entity: &str, game_function: &str) -> Result<EntityRef, String> {
+        EntityRef::resolve(entity.to_string(), &files(), &mod_api(), game_function, 0)
+    }
+
+    #[test]
+    fn finds_the_file_of_the_entity() {
+        let cow = resolve("animals:cow", "pet").unwrap();
+
+        assert_eq!(cow.entity(), "animals:cow");
+        assert_eq!(cow.entity_type(), "Animal");
+        assert_eq!(
+            cow.file().relative_path(),
+            Path::new("animals/cow-Animal.grug")
+        );
+    }
+
+    #[test]
+    fn checks_the_entity_type() {
+        let error = resolve("animals:tractor", "pet").err().unwrap();
+
+        assert_eq!(
+            error,
+            "The entity `animals:tractor` given to `pet` is a `Vehicle`, but it needs to be a `Animal`"
+        );
+
+        // An empty `entity_type` takes any entity
+        assert!(resolve("animals:tractor", "look_at").is_ok());
+    }
+
+    #[test]
+    fn suggests_close_entities() {
+        let error = resolve("animals:caw", "pet").err().unwrap();
+
+        assert!(
+            error.contains("`animals:caw` is not a loaded entity"),
+            "{error}"
+        );
+        assert!(error.contains("animals:cow"), "{error}");
+    }
+
+    #[test]
+    fn needs_a_running_on_function() {
+        let error = unsafe { EntityRef::from_game_function(c"animals:cow".as_ptr(), "pet", 0) }
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            error,
+            "`pet` was given the entity `animals:cow` outside of an on_function"
+        );
+
+        let error = unsafe { EntityRef::from_game_function(null(), "pet", 0) }
+            .err()
+            .unwrap();
+
+        assert_eq!(error, "`pet` was given a null entity");
+    }
+}
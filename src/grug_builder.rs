@@ -0,0 +1,466 @@
+use std::{
+    collections::HashMap,
+    ffi::CString,
+    fs::{create_dir_all, metadata, read_dir},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use grug_sys::*;
+use semver::Version;
+
+use crate::{
+    CommandQueue, ErrorHandler, Grug, GrugError, ModProfile, ReentrancyPolicy,
+    default_runtime_error_handler,
+    mod_api_builder::ImplementedGameFunction,
+    mod_api_type::ModAPI,
+    mod_conflicts::ConflictPolicy,
+    mod_info::{CompatibilityPolicy, load_mods},
+    resource,
+    to_string_wrapper::ToStringWrapper,
+};
+
+/// Configures and initializes grug
+///
+/// # Example
+/// ```no_run
+/// use grug_rs::{Grug, ModProfile, ReentrancyPolicy, mod_info::CompatibilityPolicy};
+///
+/// # fn main() -> Result<(), grug_rs::GrugError> {
+/// let grug = Grug::builder("./mod_api.json", "./mods", "./mods_dll")
+///     .timeout_ms(1000)
+///     .reentrancy_policy(ReentrancyPolicy::Defer)
+///     .game_version("1.4.0")
+///     .compatibility_policy(CompatibilityPolicy::Skip)
+///     .profile(ModProfile::load("./profile.json")?)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct GrugBuilder {
+    error_handler: ErrorHandler,
+    mod_api_path: PathBuf,
+    /// Written to `mod_api_path` before initializing, when built from an in-memory mod API
+    mod_api: Option<ModAPI>,
+    /// A directory of fragments or a TOML or YAML file,
+    /// converted and written to `mod_api_path` before initializing
+    mod_api_source: Option<PathBuf>,
+    mods_folder: PathBuf,
+    mods_dll_folder: PathBuf,
+    timeout_ms: u64,
+    reentrancy_policy: ReentrancyPolicy,
+    game_version: Option<String>,
+    compatibility_policy: CompatibilityPolicy,
+    conflict_policy: ConflictPolicy,
+    profile: ModProfile,
+}
+
+impl GrugBuilder {
+    /// A mod API that isn't JSON, like a `mod_api.toml` with the `toml` feature,
+    /// is converted to `mod_api.json` inside of `mods_dll_folder` for grug
+    pub fn new<P1, P2, P3>(mod_api_path: P1, mods_folder: P2, mods_dll_folder: P3) -> Self
+    where
+        P1: Into<PathBuf>,
+        P2: Into<PathBuf>,
+        P3: Into<PathBuf>,
+    {
+        let mod_api_path = mod_api_path.into();
+        let mods_dll_folder = mods_dll_folder.into();
+
+        let (mod_api_path, mod_api_source) = match mod_api_path.extension() {
+            Some(extension) if extension != "json" => {
+                (mods_dll_folder.join("mod_api.json"), Some(mod_api_path))
+            }
+            _ => (mod_api_path, None),
+        };
+
+        Self {
+            error_handler: default_runtime_error_handler,
+            mod_api_path,
+            mod_api: None,
+            mod_api_source,
+            mods_folder: mods_folder.into(),
+            mods_dll_folder,
+            timeout_ms: 1000,
+            reentrancy_policy: ReentrancyPolicy::default(),
+            game_version: None,
+            compatibility_policy: CompatibilityPolicy::default(),
+            conflict_policy: ConflictPolicy::default(),
+            profile: ModProfile::default(),
+        }
+    }
+
+    /// Configures grug with an in-memory mod API instead of a `mod_api.json`
+    ///
+    /// grug reads the mod API from a file, so it's written to `mod_api.json` inside of `mods_dll_folder`.
+    pub fn from_mod_api<P1, P2>(mod_api: ModAPI, mods_folder: P1, mods_dll_folder: P2) -> Self
+    where
+        P1: Into<PathBuf>,
+        P2: Into<PathBuf>,
+    {
+        let mods_dll_folder = mods_dll_folder.into();
+
+        Self {
+            mod_api: Some(mod_api),
+            ..Self::new(
+                mods_dll_folder.join("mod_api.json"),
+                mods_folder,
+                mods_dll_folder,
+            )
+        }
+    }
+
+    /// Configures grug with a directory of mod API fragments, see `ModAPI::load_dir`
+    ///
+    /// The merged mod API is written to `mod_api.json` inside of `mods_dll_folder` for grug,
+    /// and `Grug::reload_mod_api` merges the fragments again.
+    pub fn from_mod_api_dir<P1, P2, P3>(
+        mod_api_dir: P1,
+        mods_folder: P2,
+        mods_dll_folder: P3,
+    ) -> Self
+    where
+        P1: Into<PathBuf>,
+        P2: Into<PathBuf>,
+        P3: Into<PathBuf>,
+    {
+        let mods_dll_folder = mods_dll_folder.into();
+
+        Self {
+            mod_api_source: Some(mod_api_dir.into()),
+            ..Self::new(
+                mods_dll_folder.join("mod_api.json"),
+                mods_folder,
+                mods_dll_folder,
+            )
+        }
+    }
+
+    /// Defaults to `default_runtime_error_handler`
+    pub fn error_handler(mut self, error_handler: ErrorHandler) -> Self {
+        self.error_handler = error_handler;
+        self
+    }
+
+    /// How long an `on_function` may run for, defaults to 1000
+    pub fn timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.timeout_ms = timeout_ms;
+        self
+    }
+
+    pub fn reentrancy_policy(mut self, policy: ReentrancyPolicy) -> Self {
+        self.reentrancy_policy = policy;
+        self
+    }
+
+    /// The semver version of your game, which every mod's `game_version` is checked against
+    pub fn game_version<S: ToString>(mut self, version: S) -> Self {
+        self.game_version = Some(version.to_string());
+        self
+    }
+
+    /// What to do with mods that are incompatible with the game version, defaults to using them anyway
+    pub fn compatibility_policy(mut self, policy: CompatibilityPolicy) -> Self {
+        self.compatibility_policy = policy;
+        self
+    }
+
+    /// What to do when several mods define the same entity, defaults to running all of them
+    pub fn conflict_policy(mut self, policy: ConflictPolicy) -> Self {
+        self.conflict_policy = policy;
+        self
+    }
+
+    /// Which mods are enabled and their load order, defaults to every mod sorted by name
+    pub fn profile(mut self, profile: ModProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Initializes grug.
+    /// You should only do this once or bad things will happen.
+    pub fn build(self) -> Result<Grug, GrugError> {
+        let Self {
+            error_handler,
+            mod_api_path,
+            mod_api,
+            mod_api_source,
+            mods_folder,
+            mods_dll_folder,
+            timeout_ms,
+            reentrancy_policy,
+            game_version,
+            compatibility_policy,
+            conflict_policy,
+            profile,
+        } = self;
+
+        let mod_api = match &mod_api_source {
+            Some(mod_api_source) => Some(ModAPI::load_file_or_dir(mod_api_source)?),
+            None => mod_api,
+        };
+
+        if let Some(mod_api) = mod_api {
+            create_dir_all(&mods_dll_folder).map_err(|x| GrugError::WriteModAPI {
+                path: mod_api_path.clone(),
+                error: x.to_string(),
+            })?;
+            mod_api.save(&mod_api_path)?;
+        }
+
+        // Anything that isn't JSON has been converted by now, so this errors on files without an extension
+        let mod_api = ModAPI::load(&mod_api_path)?;
+
+        mod_api
+            .validate()
+            .map_err(|problems| GrugError::InvalidModAPI {
+                path: mod_api_path.clone(),
+                problems,
+            })?;
+
+        // grug only reports these once a mod calls them, with a cryptic symbol error
+        check_game_functions(&mod_api)?;
+
+        let game_version = game_version
+            .map(|version| {
+                Version::parse(&version).map_err(|x| GrugError::GameVersion {
+                    version: version.clone(),
+                    error: x.to_string(),
+                })
+            })
+            .transpose()?;
+
+        let mods = load_mods(&mods_folder, game_version.as_ref())?;
+
+        if let Some(game_version) = &game_version {
+            let incompatible: Vec<String> = mods
+                .iter()
+                .filter(|mod_| !mod_.compatibility.is_compatible())
+                .map(|mod_| format!("`{}` ({})", mod_.dir_name, mod_.info.game_version))
+                .collect();
+
+            if !incompatible.is_empty() && compatibility_policy == CompatibilityPolicy::Refuse {
+                return Err(GrugError::IncompatibleMods {
+                    game_version: game_version.to_string(),
+                    mods: incompatible,
+                });
+            }
+        }
+
+        let entities = on_function_indices(&mod_api);
+        let mod_api_modified = modified_time(mod_api_source.as_ref().unwrap_or(&mod_api_path));
+
+        let mut grug = Grug {
+            mod_api,
+            mod_api_path: mod_api_path.clone(),
+            mod_api_modified,
+            mod_api_source,
+            entities,
+            mods,
+            commands: CommandQueue::new(),
+            reentrancy_policy,
+            compatibility_policy,
+            conflict_policy,
+            profile,
+            load_order: vec![],
+            running: Mutex::new(()),
+            file_cache: Mutex::new(None),
+        };
+
+        resource::set_context(&mods_folder, &grug.mod_api);
+
+        // Check dependencies before grug starts loading the mods
+        grug.load_order = grug.resolve_load_order()?;
+
+        // Initialize grug
+        let result = unsafe {
+            grug_init(
+                Some(error_handler),
+                CString::new(mod_api_path.as_os_str().to_string_lossy().to_string())
+                    .unwrap()
+                    .as_ptr(),
+                CString::new(mods_folder.as_os_str().to_string_lossy().to_string())
+                    .unwrap()
+                    .as_ptr(),
+                CString::new(mods_dll_folder.as_os_str().to_string_lossy().to_string())
+                    .unwrap()
+                    .as_ptr(),
+                timeout_ms,
+            )
+        };
+
+        if result {
+            #[allow(static_mut_refs)]
+            let error = unsafe { grug_error }; // SAFETY: This implements the copy trait so it's safe to use
+            return Err(GrugError::Init {
+                error: error.msg.to_string(),
+            });
+        }
+
+        Ok(grug)
+    }
+}
+
+/// The index of every `on_function` in grug's `on_fns` of its entity type
+pub(crate) fn on_function_indices(mod_api: &ModAPI) -> HashMap<String, HashMap<String, usize>> {
+    mod_api
+        .entities
+        .iter()
+        .map(|(name, entity)| {
+            let indices = entity
+                .on_functions
+                .keys()
+                .enumerate()
+                .map(|(i, on_function)| (on_function.clone(), i))
+                .collect();
+
+            (name.clone(), indices)
+        })
+        .collect()
+}
+
+/// When a file was last modified, if the platform supports it
+///
+/// For a directory it's the latest of the directory itself and the files in it,
+/// so adding, removing and editing files all count.
+pub(crate) fn modified_time(path: &Path) -> Option<SystemTime> {
+    let modified = metadata(path).and_then(|metadata| metadata.modified()).ok();
+
+    let Ok(entries) = read_dir(path) else {
+        return modified;
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+        .chain(modified)
+        .max()
+}
+
+/// Checks that every game function of the mod API is implemented with `#[game_function]`,
+/// and on unix that grug can find its `game_fn_<name>` symbol, which needs `-rdynamic`
+///
+/// Symbols can't be looked up the same way outside of unix,
+/// so there only game functions implemented with `#[game_function]` count.
+fn check_game_functions(mod_api: &ModAPI) -> Result<(), GrugError> {
+    let implemented: Vec<&str> = inventory::iter::<ImplementedGameFunction>
+        .into_iter()
+        .map(|game_function| game_function.name)
+        .collect();
+
+    // Hand-written `game_fn_<name>` symbols count too, where they can be looked up
+    #[cfg(unix)]
+    let is_implemented = |name: &str| implemented.contains(&name) || is_exported(name);
+    #[cfg(not(unix))]
+    let is_implemented = |name: &str| implemented.contains(&name);
+
+    let missing: Vec<String> = mod_api
+        .game_functions
+        .keys()
+        .filter(|name| !is_implemented(name))
+        .cloned()
+        .collect();
+
+    if !missing.is_empty() {
+        return Err(GrugError::MissingGameFunctions { names: missing });
+    }
+
+    #[cfg(unix)]
+    {
+        let unexported: Vec<String> = mod_api
+            .game_functions
+            .keys()
+            .filter(|name| !is_exported(name))
+            .cloned()
+            .collect();
+
+        if !unexported.is_empty() {
+            return Err(GrugError::UnexportedGameFunctions { names: unexported });
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether grug can find the `game_fn_<name>` symbol of a game function in this process
+#[cfg(unix)]
+fn is_exported(name: &str) -> bool {
+    let symbol = CString::new(format!("game_fn_{name}")).unwrap();
+    !unsafe { libc::dlsym(libc::RTLD_DEFAULT, symbol.as_ptr()) }.is_null()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mod_api_type::GameFunction;
+
+    inventory::submit! {
+        ImplementedGameFunction { name: "not_exported" }
+    }
+
+    fn check(name: &str) -> Result<(), GrugError> {
+        check_game_functions(
+            &ModAPI::builder()
+                .game_function(name, GameFunction::new("Does something"))
+                .build(),
+        )
+    }
+
+    #[test]
+    fn unimplemented_game_functions_are_missing() {
+        let Err(GrugError::MissingGameFunctions { names }) = check("not_implemented") else {
+            panic!("`not_implemented` isn't implemented");
+        };
+        assert_eq!(names, ["not_implemented"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn implemented_game_functions_need_their_symbol() {
+        let Err(GrugError::UnexportedGameFunctions { names }) = check("not_exported") else {
+            panic!("`game_fn_not_exported` doesn't exist");
+        };
+        assert_eq!(names, ["not_exported"]);
+    }
+
+    #[test]
+    fn missing_or_extensionless_mod_api() {
+        let Err(GrugError::ReadModAPI { path, .. }) =
+            GrugBuilder::new("does_not_exist.json", "mods", "mods_dll").build()
+        else {
+            panic!("`does_not_exist.json` doesn't exist");
+        };
+        assert_eq!(path, Path::new("does_not_exist.json"));
+
+        let Err(GrugError::ModAPIFormat { path }) =
+            GrugBuilder::new("Cargo", "mods", "mods_dll").build()
+        else {
+            panic!("`Cargo` has no extension");
+        };
+        assert_eq!(path, Path::new("Cargo"));
+    }
+
+    #[test]
+    fn missing_mods_folder() {
+        let Err(GrugError::ModsFolder { path, .. }) = GrugBuilder::new(
+            "examples/generated_mod_api/mod_api.json",
+            "does_not_exist",
+            "mods_dll",
+        )
+        .build() else {
+            panic!("`does_not_exist` doesn't exist");
+        };
+        assert_eq!(path, Path::new("does_not_exist"));
+    }
+
+    #[test]
+    fn no_game_functions() {
+        assert!(check_game_functions(&ModAPI::builder().build()).is_ok());
+    }
+}
@@ -5,7 +5,7 @@ use std::{
     marker::PhantomData,
 };
 
-use crate::OpaqueGrugType;
+use crate::{OpaqueGrugType, mod_api_type::GrugType};
 
 pub struct CustomValue<'a> {
     raw: *mut c_void,
@@ -33,6 +33,66 @@ impl<'a> GrugValue<'a> {
     pub fn custom<T: Any + 'static>(value: &'a mut T) -> Self {
         Self::Custom(CustomValue::new(value))
     }
+
+    /// Whether grug can be given this value as `type_`
+    ///
+    /// Resources and entities are given as strings, ids as custom values.
+    pub fn matches(&self, type_: &GrugType) -> bool {
+        matches!(
+            (self, type_),
+            (
+                GrugValue::String(_),
+                GrugType::String | GrugType::Resource { .. } | GrugType::Entity { .. }
+            ) | (GrugValue::I32(_), GrugType::I32)
+                | (GrugValue::F32(_), GrugType::F32)
+                | (GrugValue::Bool(_), GrugType::Bool)
+                | (GrugValue::Custom(_), GrugType::Id | GrugType::Custom(_))
+        )
+    }
+
+    /// Like the names of `GrugType`, with `custom` for custom values
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            GrugValue::String(_) => "string",
+            GrugValue::I32(_) => "i32",
+            GrugValue::F32(_) => "f32",
+            GrugValue::Bool(_) => "bool",
+            GrugValue::Custom(_) => "custom",
+        }
+    }
+
+    /// Copies the value, returns `None` for custom values
+    pub fn to_owned_value(&self) -> Option<OwnedGrugValue> {
+        match self {
+            GrugValue::String(v) => Some(OwnedGrugValue::String(v.clone())),
+            GrugValue::I32(v) => Some(OwnedGrugValue::I32(*v)),
+            GrugValue::F32(v) => Some(OwnedGrugValue::F32(*v)),
+            GrugValue::Bool(v) => Some(OwnedGrugValue::Bool(*v)),
+            GrugValue::Custom(_) => None,
+        }
+    }
+}
+
+/// A `GrugValue` that doesn't borrow anything, so it can be stored for later
+///
+/// Custom values can't be stored since they borrow from the caller.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OwnedGrugValue {
+    String(String),
+    I32(i32),
+    F32(f32),
+    Bool(bool),
+}
+
+impl From<OwnedGrugValue> for GrugValue<'_> {
+    fn from(value: OwnedGrugValue) -> Self {
+        match value {
+            OwnedGrugValue::String(v) => GrugValue::String(v),
+            OwnedGrugValue::I32(v) => GrugValue::I32(v),
+            OwnedGrugValue::F32(v) => GrugValue::F32(v),
+            OwnedGrugValue::Bool(v) => GrugValue::Bool(v),
+        }
+    }
 }
 
 /// Arguments to a grug function
@@ -75,6 +135,11 @@ impl<'a> Arguments<'a> {
         }
     }
 
+    /// Copies every value, returns `None` if any of them is a custom value
+    pub fn to_owned_values(&self) -> Option<Vec<OwnedGrugValue>> {
+        self.values.iter().map(GrugValue::to_owned_value).collect()
+    }
+
     pub fn into_raw(&mut self) -> *mut *mut c_void {
         let mut opaque_values = Vec::with_capacity(self.values.len());
 
@@ -79,36 +79,129 @@
 
 //! Then run your program!
 
-//! If there are errors with unable to find symbols you might have to create a basic `build.rs` that looks like this:
+//! Pass `api` to `#[game_function]` to check its arguments and return type against the mod API at compile time,
+//! relative to your `Cargo.toml`:
+//! ```no_run
+//! use grug_rs_proc_macro::game_function;
+//!
+//! #[game_function(api = "examples/on_argument/mod_api.json")]
+//! fn println_int(msg: i32) {
+//!     println!("{msg}");
+//! }
+//! ```
+//!
+//! So this doesn't compile, since `msg` is an `i32` in the mod API:
+//! ```compile_fail
+//! use grug_rs_proc_macro::game_function;
+//!
+//! #[game_function(api = "examples/on_argument/mod_api.json")]
+//! fn println_int(msg: String) {
+//!     println!("{msg}");
+//! }
+//! ```
+
+//! With the `toml` or `yaml` feature, the mod API can also be a `mod_api.toml` or `mod_api.yaml`, which is converted to JSON for grug.
+//!
+//! `build::generate` writes typed bindings of your mod API in your `build.rs`. Build scripts can turn off the default `runtime` feature with `default-features = false`, so grug itself isn't built for them.
+//!
+//! With the `schemars` feature, `schema::generate` writes JSON Schemas of `mod_api.json` and `about.json` for editors, and the `jsonschema` feature adds `schema::check_mod_api` and `schema::check_mod_info` to check files against them.
+
+//! On unix, grug finds game functions by their symbol, so if `Grug::new` fails with `GrugError::UnexportedGameFunctions` you have to create a basic `build.rs` that looks like this:
 //! ```rs
 //! fn main() {
 //!     println!("cargo:rustc-link-arg=-rdynamic");
 //! }
 //! ```
 
+// So the paths `#[game_function]` generates also work in grug-rs's own examples and tests
+extern crate self as grug_rs;
+
+#[cfg(feature = "runtime")]
 pub use grug_sys;
+pub use semver;
 
+pub mod build;
+#[cfg(feature = "runtime")]
+pub mod command_queue;
+pub mod docs;
+#[cfg(feature = "runtime")]
+pub mod entity_ref;
+#[cfg(feature = "runtime")]
+pub mod grug_builder;
+#[cfg(feature = "runtime")]
 pub mod grug_value;
+pub mod mod_api_builder;
+pub mod mod_api_diff;
 pub mod mod_api_type;
+#[cfg(feature = "runtime")]
+pub mod mod_conflicts;
+pub mod mod_dependencies;
+pub mod mod_info;
+pub mod mod_profile;
+#[cfg(feature = "runtime")]
+pub mod mod_tree;
+#[cfg(feature = "runtime")]
+pub mod resource;
+#[cfg(feature = "schemars")]
+pub mod schema;
+#[cfg(feature = "runtime")]
 mod to_string_wrapper;
 
+/// What the code generated by `#[error_handler]` and `#[game_function]` uses, not part of the API
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "runtime")]
+    pub use grug_sys::grug_runtime_error_type;
+    pub use inventory;
+
+    pub use crate::mod_api_builder::{ImplementedGameFunction, RegisteredGameFunction};
+    #[cfg(feature = "runtime")]
+    pub use crate::{
+        EntityRef, GrugRuntimeError, game_function_error,
+        resource::{Resource, load},
+    };
+}
+
+use std::path::PathBuf;
+#[cfg(feature = "runtime")]
 use std::{
-    alloc::{Layout, alloc},
-    collections::HashMap,
-    ffi::{CStr, CString, OsString, c_char, c_void},
-    fs::read_to_string,
-    path::PathBuf,
-    ptr::null_mut,
-    slice::{from_raw_parts, from_raw_parts_mut},
+    cell::Cell,
+    collections::{HashMap, HashSet},
+    ffi::{CStr, CString, c_char, c_void},
+    path::Path,
+    sync::{Arc, Mutex, MutexGuard},
+    time::SystemTime,
 };
 
+#[cfg(feature = "runtime")]
 use grug_sys::*;
-use seq_macro::seq;
-use serde_json::from_str;
+#[cfg(feature = "runtime")]
+use strsim::levenshtein;
 use thiserror::Error;
 
-pub use crate::grug_value::{Arguments, GrugValue};
-use crate::{mod_api_type::ModAPI, to_string_wrapper::ToStringWrapper};
+#[cfg(feature = "runtime")]
+pub use crate::command_queue::{Command, CommandQueue};
+#[cfg(feature = "runtime")]
+pub use crate::entity_ref::EntityRef;
+#[cfg(feature = "runtime")]
+pub use crate::grug_builder::GrugBuilder;
+#[cfg(feature = "runtime")]
+pub use crate::grug_value::{Arguments, GrugValue, OwnedGrugValue};
+pub use crate::mod_api_builder::ModApiBuilder;
+pub use crate::mod_info::{LoadedMod, ModInfo};
+pub use crate::mod_profile::ModProfile;
+#[cfg(feature = "runtime")]
+pub use crate::mod_tree::{GrugFile, ModDir};
+#[cfg(feature = "runtime")]
+use crate::{
+    grug_builder::modified_time,
+    mod_api_diff::{Change, needing_a_restart},
+    mod_api_type::ModAPI,
+    mod_conflicts::{Conflict, ConflictPolicy, find_conflicts},
+    mod_dependencies::resolve_load_order,
+    mod_info::CompatibilityPolicy,
+    to_string_wrapper::ToStringWrapper,
+};
 
 /// Errors from Grug
 #[derive(Error, Debug)]
@@ -117,8 +210,54 @@ pub enum GrugError {
     Init { error: String },
     #[error("Failed to read: `{path}`: `{error}`")]
     ReadModAPI { path: PathBuf, error: String },
-    #[error("Failed to deserialize `{path}`: `{error}`")]
-    Deserialize { path: PathBuf, error: String },
+    #[error("Failed to deserialize `{path}`{}: `{error}`", position(*.line, *.column))]
+    Deserialize {
+        path: PathBuf,
+        /// Starts at 1, 0 if the parser doesn't know
+        line: usize,
+        column: usize,
+        error: String,
+    },
+    #[error("Enable the `{feature}` feature of grug-rs to load `{path}`")]
+    ModAPIFeature {
+        path: PathBuf,
+        feature: &'static str,
+    },
+    #[error("`{path}` isn't a mod API, which has to be a `.json`, `.toml`, `.yaml` or `.yml` file")]
+    ModAPIFormat { path: PathBuf },
+    #[error("Invalid mod API `{path}`: {}", .problems.join(", "))]
+    InvalidModAPI {
+        path: PathBuf,
+        problems: Vec<String>,
+    },
+    #[error("Failed to write `{path}`: `{error}`")]
+    WriteModAPI { path: PathBuf, error: String },
+    #[error("Conflicting mod API fragments: {}", .conflicts.join(", "))]
+    ModAPIConflicts { conflicts: Vec<String> },
+    #[error(
+        "Game functions declared in the mod API aren't implemented: {}. Implement them with `#[game_function]`",
+        .names.join(", ")
+    )]
+    MissingGameFunctions { names: Vec<String> },
+    #[error(
+        "Game functions are implemented but grug can't find them: {}. Make sure your `build.rs` has `println!(\"cargo:rustc-link-arg=-rdynamic\");`",
+        .names.join(", ")
+    )]
+    UnexportedGameFunctions { names: Vec<String> },
+    #[error("Failed to write bindings to `{path}`: `{error}`")]
+    WriteBindings { path: PathBuf, error: String },
+    #[error("Names in the mod API turn into the same Rust identifier: {}", .duplicates.join(", "))]
+    DuplicateIdentifiers { duplicates: Vec<String> },
+    #[error("Failed to write docs to `{path}`: `{error}`")]
+    WriteDocs { path: PathBuf, error: String },
+    #[error("Failed to write schema to `{path}`: `{error}`")]
+    WriteSchema { path: PathBuf, error: String },
+    #[error("Failed to read `{path}`: `{error}`")]
+    ReadFile { path: PathBuf, error: String },
+    /// Each error starts with the JSON pointer of where it is, like `/entities/World/description`,
+    /// or with `root`
+    #[error("`{path}` doesn't match its schema: {}", .errors.join(", "))]
+    SchemaViolations { path: PathBuf, errors: Vec<String> },
     #[error("`{function_name}` is not a on_function")]
     NotAnOnFunction { function_name: String },
     #[error("`{entity_name}` is not an entity")]
@@ -129,8 +268,113 @@ pub enum GrugError {
     Regenerating { error: String },
     #[error("Grug function not defined")]
     UndefinedFunction,
+    #[error("`{action}` was called while an on_function was running")]
+    Reentrant { action: String },
+    #[error("Failed to read the mods folder `{path}`: `{error}`")]
+    ModsFolder { path: PathBuf, error: String },
+    #[error("Invalid mod info `{path}`: {}", .problems.join(", "))]
+    ModInfo {
+        path: PathBuf,
+        problems: Vec<String>,
+    },
+    #[error("Invalid game version `{version}`: `{error}`")]
+    GameVersion { version: String, error: String },
+    #[error("Mods incompatible with game version `{game_version}`: {}", .mods.join(", "))]
+    IncompatibleMods {
+        game_version: String,
+        mods: Vec<String>,
+    },
+    #[error("`{name}` was made for game version `{game_version}` and failed to load: `{error}`")]
+    IncompatibleMod {
+        name: String,
+        game_version: String,
+        error: String,
+    },
+    #[error("Failed to use mod profile `{path}`: `{error}`")]
+    ModProfile { path: PathBuf, error: String },
+    #[error("`{name}` depends on `{dependency}` `{requirement}`, which isn't loaded")]
+    MissingDependency {
+        name: String,
+        dependency: String,
+        requirement: String,
+    },
+    #[error(
+        "`{name}` depends on `{dependency}` `{requirement}`, but version `{version}` is loaded"
+    )]
+    DependencyVersion {
+        name: String,
+        dependency: String,
+        requirement: String,
+        version: String,
+    },
+    #[error("Mods depend on each other in a cycle: {}", .mods.join(" -> "))]
+    DependencyCycle { mods: Vec<String> },
+    #[error(
+        "`{on_function}` takes ({}), but was given ({})",
+        .expected.join(", "),
+        .given.join(", ")
+    )]
+    WrongArguments {
+        on_function: String,
+        expected: Vec<String>,
+        given: Vec<String>,
+    },
+    #[error("Conflicting mods: {}", .conflicts.join(", "))]
+    Conflicts { conflicts: Vec<String> },
+    #[error("`{entity}` is not a loaded entity{}", did_you_mean(.suggestions))]
+    UnknownEntity {
+        entity: String,
+        suggestions: Vec<String>,
+    },
+}
+
+fn position(line: usize, column: usize) -> String {
+    if line == 0 {
+        String::new()
+    } else {
+        format!(" at line {line}, column {column}")
+    }
+}
+
+fn did_you_mean(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        String::new()
+    } else {
+        format!(", did you mean: `{}`", suggestions.join("`, `"))
+    }
+}
+
+#[cfg(feature = "runtime")]
+/// Finds up to 5 candidates that look like `query`, closest first
+///
+/// Entities are compared both with and without their mod,
+/// so `goblin` and `base:gobln` both match `base:goblin`.
+fn close_matches<'a, I: IntoIterator<Item = &'a str>>(query: &str, candidates: I) -> Vec<String> {
+    fn without_mod(entity: &str) -> &str {
+        entity.split_once(':').map_or(entity, |(_, name)| name)
+    }
+
+    let mut matches: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .map(|candidate| {
+            let distance = levenshtein(query, candidate)
+                .min(levenshtein(without_mod(query), without_mod(candidate)));
+            (distance, candidate)
+        })
+        .filter(|(distance, candidate)| *distance <= (candidate.len() / 3).max(2))
+        .collect();
+
+    matches.sort();
+    matches.dedup();
+
+    matches
+        .into_iter()
+        .take(5)
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
 }
 
+#[cfg(feature = "runtime")]
 #[repr(C)]
 pub enum GrugRuntimeError {
     DivisionByZero,
@@ -140,9 +384,11 @@ pub enum GrugRuntimeError {
     GameFnError,
 }
 
+#[cfg(feature = "runtime")]
 pub type ErrorHandler =
     unsafe extern "C" fn(*const c_char, grug_runtime_error_type, *const c_char, *const c_char);
 
+#[cfg(feature = "runtime")]
 /// Default error handler for grug-rs
 ///
 /// # Safety
@@ -178,16 +424,156 @@ pub unsafe extern "C" fn default_runtime_error_handler(
     );
 }
 
+#[cfg(feature = "runtime")]
+/// What to do when an `on_function` is activated while another one is running on the same thread,
+/// for example from inside of a game function
+///
+/// Activations from other threads wait for the running `on_function` to finish instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReentrancyPolicy {
+    /// Return `GrugError::Reentrant`
+    #[default]
+    Error,
+    /// Push the activation into the command queue, to be run by `Grug::flush_commands`.
+    /// Activations with custom arguments can't be deferred and still error.
+    Defer,
+}
+
+#[cfg(feature = "runtime")]
+/// What `Grug::reload_mod_api` did
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModAPIReload {
+    /// The mod API wasn't modified since it was last loaded, see `Grug::reload_mod_api_if_modified`
+    Unchanged,
+    /// Only descriptions changed, and they were reloaded
+    Reloaded,
+    /// The mod API changed in ways grug can only load after a restart, so the current one is kept
+    ///
+    /// The game can tell the player to restart, or restart itself.
+    RestartRequired { changes: Vec<Change> },
+}
+
+#[cfg(feature = "runtime")]
 pub struct Grug {
-    #[allow(dead_code)]
-    mod_api: ModAPI, // Here just in case
+    mod_api: ModAPI,
+    mod_api_path: PathBuf,
+    /// When `mod_api_path`, or `mod_api_source` if there is one, was modified as of the last (re)load
+    mod_api_modified: Option<SystemTime>,
+    /// The fragments directory or TOML or YAML file `mod_api_path` is converted from
+    mod_api_source: Option<PathBuf>,
     entities: HashMap<String, HashMap<String, usize>>,
+    mods: Vec<LoadedMod>,
+    commands: CommandQueue,
+    reentrancy_policy: ReentrancyPolicy,
+    compatibility_policy: CompatibilityPolicy,
+    conflict_policy: ConflictPolicy,
+    profile: ModProfile,
+    /// Directory names of the active mods, dependencies before the mods that need them
+    load_order: Vec<String>,
+    /// Held while an `on_function` runs, since grug can only run one at a time
+    running: Mutex<()>,
+    /// Built on first use, and thrown away when the profile or conflict policy changes
+    file_cache: Mutex<Option<Arc<FileCache>>>,
+}
+
+#[cfg(feature = "runtime")]
+/// The files of the active mods, copied from grug's mod tree once instead of on every activation
+///
+/// Rebuilt when grug reloads, adds or removes a file, see `mod_tree::track_changes`.
+struct FileCache {
+    generation: u64,
+    /// Every file of the active mods in load order, including overridden ones
+    active: Vec<GrugFile>,
+    /// Indices into `active` of the files that get run, leaving out overridden ones
+    running: Vec<usize>,
+    /// Indices into `running`, by entity type
+    by_entity_type: HashMap<String, Vec<usize>>,
+    /// Indices into `active`, by entity
+    by_entity: HashMap<String, usize>,
+    conflicts: Vec<Conflict>,
+}
+
+#[cfg(feature = "runtime")]
+impl FileCache {
+    fn file_by_entity(&self, entity: String) -> Result<GrugFile, GrugError> {
+        if let Some(&i) = self.by_entity.get(&entity) {
+            return Ok(self.active[i].clone());
+        }
+
+        Err(GrugError::UnknownEntity {
+            suggestions: close_matches(&entity, self.active.iter().map(GrugFile::entity)),
+            entity,
+        })
+    }
+}
+
+#[cfg(feature = "runtime")]
+// SAFETY: The files are copies that are never written to,
+// and their pointers point into grug itself, which `Grug` is already shared as.
+unsafe impl Send for FileCache {}
+#[cfg(feature = "runtime")]
+unsafe impl Sync for FileCache {}
+
+#[cfg(feature = "runtime")]
+thread_local! {
+    /// The grug running an `on_function` on this thread, so game functions can reach it
+    static RUNNING: Cell<*const Grug> = const { Cell::new(std::ptr::null()) };
 }
 
+#[cfg(feature = "runtime")]
+/// Marks this thread as running an `on_function` until dropped, and keeps other threads from using grug
+struct OnFunctionGuard<'a> {
+    _running: MutexGuard<'a, ()>,
+}
+
+#[cfg(feature = "runtime")]
+impl<'a> OnFunctionGuard<'a> {
+    /// Returns `None` if this thread is already running an `on_function`,
+    /// and waits for other threads to finish theirs
+    fn enter(grug: &'a Grug) -> Option<Self> {
+        if !RUNNING.get().is_null() {
+            return None;
+        }
+
+        let running = grug.running.lock().unwrap();
+        RUNNING.set(grug);
+
+        Some(Self { _running: running })
+    }
+}
+
+#[cfg(feature = "runtime")]
+impl Drop for OnFunctionGuard<'_> {
+    fn drop(&mut self) {
+        // Before the lock is released
+        RUNNING.set(std::ptr::null());
+    }
+}
+
+#[cfg(feature = "runtime")]
+/// Calls `f` with the grug running an `on_function` on this thread, if there is one
+pub(crate) fn with_running_grug<R>(f: impl FnOnce(&Grug) -> R) -> Option<R> {
+    let grug = RUNNING.get();
+
+    // Only set while an `OnFunctionGuard` borrows the grug
+    (!grug.is_null()).then(|| f(unsafe { &*grug }))
+}
+
+#[cfg(feature = "runtime")]
+/// Reports an error from inside of a game function to grug,
+/// which stops the `on_function` and calls the error handler
+pub fn game_function_error(message: &str) {
+    let message = CString::new(message.replace('\0', "")).unwrap();
+    unsafe { grug_game_function_error_happened(message.as_ptr()) };
+}
+
+#[cfg(feature = "runtime")]
 impl Grug {
     /// Initializes grug for usage.
     /// You should only do this once or bad things will happen.
     ///
+    /// See `Grug::builder` for more options.
+    ///
     /// # Example
     /// ```rs
     /// let grug = Grug::new(
@@ -209,81 +595,80 @@ impl Grug {
         P2: Into<PathBuf>,
         P3: Into<PathBuf>,
     {
-        let mod_api_path: PathBuf = mod_api_path.into();
-        let mods_folder: PathBuf = mods_folder.into();
-        let mods_dll_folder: PathBuf = mods_dll_folder.into();
+        let mut builder =
+            Self::builder(mod_api_path, mods_folder, mods_dll_folder).timeout_ms(timeout_ms);
 
-        assert!(mod_api_path.is_file()); // Ensure that it's a file to begin with
-        assert!(mod_api_path.extension().is_some()); // Ensure it has an extension
-        assert_eq!(
-            mod_api_path.extension().unwrap().to_os_string(),
-            OsString::from("json".to_string())
-        ); // Ensure that it's a json extension
+        if let Some(error_handler) = error_handler {
+            builder = builder.error_handler(error_handler);
+        }
 
-        assert!(!mods_folder.is_file()); // Ensure it's a folder
+        builder.build()
+    }
 
-        // We need to get the on function count
-        let mod_api_json = read_to_string(&mod_api_path).map_err(|x| GrugError::ReadModAPI {
-            path: mod_api_path.clone(),
-            error: x.to_string().clone(),
-        })?;
-        let mod_api: ModAPI = from_str(&mod_api_json).map_err(|x| GrugError::Deserialize {
-            path: mod_api_path.clone(),
-            error: x.to_string(),
-        })?;
+    /// Initializes grug with a directory of mod API fragments, see `GrugBuilder::from_mod_api_dir`
+    pub fn from_mod_api_dir<P1, P2, P3>(
+        error_handler: Option<ErrorHandler>,
+        mod_api_dir: P1,
+        mods_folder: P2,
+        mods_dll_folder: P3,
+        timeout_ms: u64,
+    ) -> Result<Self, GrugError>
+    where
+        P1: Into<PathBuf>,
+        P2: Into<PathBuf>,
+        P3: Into<PathBuf>,
+    {
+        let mut builder = GrugBuilder::from_mod_api_dir(mod_api_dir, mods_folder, mods_dll_folder)
+            .timeout_ms(timeout_ms);
 
-        // Initialize grug
-        let result = unsafe {
-            grug_init(
-                Some(error_handler.unwrap_or(default_runtime_error_handler)),
-                CString::new(mod_api_path.as_os_str().to_string_lossy().to_string())
-                    .unwrap()
-                    .as_ptr(),
-                CString::new(mods_folder.as_os_str().to_string_lossy().to_string())
-                    .unwrap()
-                    .as_ptr(),
-                CString::new(mods_dll_folder.as_os_str().to_string_lossy().to_string())
-                    .unwrap()
-                    .as_ptr(),
-                timeout_ms,
-            )
-        };
+        if let Some(error_handler) = error_handler {
+            builder = builder.error_handler(error_handler);
+        }
 
-        let entities = mod_api
-            .entities
-            .iter()
-            .map(|(name, data)| {
-                let mut i = 0;
-                (
-                    name.clone(),
-                    data.on_functions
-                        .keys()
-                        .map(|k| {
-                            let return_val = (k.clone(), i);
-                            // println!("{k}");
-                            i += 1;
-                            return_val
-                        })
-                        .collect(),
-                )
-            })
-            .collect();
+        builder.build()
+    }
 
-        if result {
-            #[allow(static_mut_refs)]
-            let error = unsafe { grug_error }; // SAFETY: This implements the copy trait so it's safe to use
-            return Err(GrugError::Init {
-                error: error.msg.to_string(),
-            });
+    /// Initializes grug with an in-memory mod API, see `GrugBuilder::from_mod_api`
+    pub fn from_mod_api<P1, P2>(
+        error_handler: Option<ErrorHandler>,
+        mod_api: ModAPI,
+        mods_folder: P1,
+        mods_dll_folder: P2,
+        timeout_ms: u64,
+    ) -> Result<Self, GrugError>
+    where
+        P1: Into<PathBuf>,
+        P2: Into<PathBuf>,
+    {
+        let mut builder =
+            GrugBuilder::from_mod_api(mod_api, mods_folder, mods_dll_folder).timeout_ms(timeout_ms);
+
+        if let Some(error_handler) = error_handler {
+            builder = builder.error_handler(error_handler);
         }
 
-        Ok(Self { mod_api, entities })
+        builder.build()
+    }
+
+    /// Creates a `GrugBuilder` for configuring grug before initializing it
+    pub fn builder<P1, P2, P3>(
+        mod_api_path: P1,
+        mods_folder: P2,
+        mods_dll_folder: P3,
+    ) -> GrugBuilder
+    where
+        P1: Into<PathBuf>,
+        P2: Into<PathBuf>,
+        P3: Into<PathBuf>,
+    {
+        GrugBuilder::new(mod_api_path, mods_folder, mods_dll_folder)
     }
 
     /// # Safety
     /// Will fail if grug is not initialized
     pub unsafe fn regenerate_modified_mods_unchecked() -> Result<(), GrugError> {
         let failed = unsafe { grug_regenerate_modified_mods() };
+        unsafe { mod_tree::track_changes(failed) };
 
         if failed {
             #[allow(static_mut_refs)]
@@ -304,8 +689,71 @@ impl Grug {
     }
 
     /// Regenerates modified mods
+    ///
+    /// Errors with `GrugError::Reentrant` if called while this thread is running an `on_function`,
+    /// and waits for other threads to finish theirs
     pub fn regenerate_modified_mods(&self) -> Result<(), GrugError> {
-        unsafe { Self::regenerate_modified_mods_unchecked() }
+        let Some(_guard) = OnFunctionGuard::enter(self) else {
+            return Err(GrugError::Reentrant {
+                action: "regenerate_modified_mods".to_string(),
+            });
+        };
+
+        self.regenerate()
+    }
+
+    /// Regenerates modified mods, blaming incompatible mods for failing to load
+    ///
+    /// Errors with `GrugError::Conflicts` under `ConflictPolicy::Reject`
+    fn regenerate(&self) -> Result<(), GrugError> {
+        unsafe { Self::regenerate_modified_mods_unchecked() }.map_err(|error| {
+            let GrugError::FileLoading { path, .. } = &error else {
+                return error;
+            };
+
+            let incompatible = self.mods.iter().find(|mod_| {
+                !mod_.compatibility.is_compatible() && Path::new(path).starts_with(&mod_.path)
+            });
+
+            match incompatible {
+                Some(mod_) => GrugError::IncompatibleMod {
+                    name: mod_.dir_name.clone(),
+                    game_version: mod_.info.game_version.clone(),
+                    error: error.to_string(),
+                },
+                None => error,
+            }
+        })?;
+
+        if self.conflict_policy == ConflictPolicy::Reject {
+            let conflicts = &self.file_cache().conflicts;
+
+            if !conflicts.is_empty() {
+                return Err(GrugError::Conflicts {
+                    conflicts: conflicts.iter().map(Conflict::to_string).collect(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets what happens when an `on_function` is activated while another one is running
+    pub fn set_reentrancy_policy(&mut self, policy: ReentrancyPolicy) {
+        self.reentrancy_policy = policy;
+    }
+
+    /// Sets what happens when several mods define the same entity
+    pub fn set_conflict_policy(&mut self, policy: ConflictPolicy) {
+        self.conflict_policy = policy;
+        self.clear_file_cache();
+    }
+
+    /// Whether this thread is running an `on_function`
+    ///
+    /// Other threads don't count, activating from them waits for the running `on_function` instead.
+    pub fn is_in_on_function(&self) -> bool {
+        std::ptr::eq(RUNNING.get(), self)
     }
 
     /// Activates an `on_function` on a given `entity`
@@ -317,6 +765,8 @@ impl Grug {
     /// grug.activate_on_function("World", "on_update").unwrap();
     /// ```
     ///
+    /// If an `on_function` is already running, the `ReentrancyPolicy` decides what happens.
+    ///
     /// # Safety
     /// Undefined behavior if arguments passed in are incorrect
     pub fn activate_on_function<S1: ToString, S2: ToString>(
@@ -325,121 +775,719 @@ impl Grug {
         on_function_name: S2,
         arguments: &mut Arguments,
     ) -> Result<(), GrugError> {
-        self.regenerate_modified_mods()?;
+        let Some(_guard) = OnFunctionGuard::enter(self) else {
+            let entity_type = entity_name.to_string();
+            let on_function = on_function_name.to_string();
 
-        let on_functions = self.entities.get(&entity_name.to_string());
+            return self.reenter(
+                format!("activate_on_function(\"{entity_type}\", \"{on_function}\")"),
+                arguments,
+                |arguments| Command::Activate {
+                    entity_type,
+                    on_function,
+                    arguments,
+                },
+            );
+        };
 
-        if on_functions.is_none() {
-            return Err(GrugError::NotAnEntity {
-                entity_name: entity_name.to_string(),
-            });
+        self.regenerate()?;
+
+        let entity_type = entity_name.to_string();
+        let on_function_name = on_function_name.to_string();
+
+        let index = self.on_function_index(&entity_type, &on_function_name)?;
+        self.check_arguments(&entity_type, &on_function_name, arguments)?;
+
+        let cache = self.file_cache();
+        let files = cache
+            .by_entity_type
+            .get(&entity_type)
+            .into_iter()
+            .flatten()
+            .map(|&i| &cache.active[cache.running[i]]);
+
+        for file in files {
+            if unsafe { !file.has_on_function(index) } {
+                continue;
+            }
+
+            unsafe { file.run_on_function(index, arguments.into_raw(), arguments.values.len())? };
         }
 
-        let index = on_functions.unwrap().get(&on_function_name.to_string());
+        Ok(())
+    }
 
-        if index.is_none() {
-            return Err(GrugError::NotAnOnFunction {
+    /// Activates an `on_function` on a single entity, like `"base_game:goblin"`
+    ///
+    /// Automatically calls `regenerate_modified_mods`
+    ///
+    /// # Example
+    /// ```rs
+    /// grug.activate_entity_on_function("base_game:goblin", "on_update", &mut Arguments::empty()).unwrap();
+    /// ```
+    ///
+    /// If an `on_function` is already running, the `ReentrancyPolicy` decides what happens.
+    ///
+    /// # Safety
+    /// Undefined behavior if arguments passed in are incorrect
+    pub fn activate_entity_on_function<S1: ToString, S2: ToString>(
+        &self,
+        entity: S1,
+        on_function_name: S2,
+        arguments: &mut Arguments,
+    ) -> Result<(), GrugError> {
+        let Some(_guard) = OnFunctionGuard::enter(self) else {
+            let entity = entity.to_string();
+            let on_function = on_function_name.to_string();
+
+            return self.reenter(
+                format!("activate_entity_on_function(\"{entity}\", \"{on_function}\")"),
+                arguments,
+                |arguments| Command::ActivateEntity {
+                    entity,
+                    on_function,
+                    arguments,
+                },
+            );
+        };
+
+        self.regenerate()?;
+
+        let file = self.get_file_by_entity(entity)?;
+        let index = self.on_function_index(file.entity_type(), &on_function_name.to_string())?;
+        self.check_arguments(file.entity_type(), &on_function_name.to_string(), arguments)?;
+
+        if unsafe { !file.has_on_function(index) } {
+            return Err(GrugError::UndefinedFunction);
+        }
+
+        unsafe { file.run_on_function(index, arguments.into_raw(), arguments.values.len()) }
+    }
+
+    /// The index of an `on_function` in grug's `on_fns` of an entity type
+    fn on_function_index(
+        &self,
+        entity_type: &str,
+        on_function_name: &str,
+    ) -> Result<usize, GrugError> {
+        let on_functions =
+            self.entities
+                .get(entity_type)
+                .ok_or_else(|| GrugError::NotAnEntity {
+                    entity_name: entity_type.to_string(),
+                })?;
+
+        on_functions
+            .get(on_function_name)
+            .copied()
+            .ok_or_else(|| GrugError::NotAnOnFunction {
                 function_name: on_function_name.to_string(),
+            })
+    }
+
+    /// Checks the arguments against the ones the mod API declares for the `on_function`
+    fn check_arguments(
+        &self,
+        entity_type: &str,
+        on_function_name: &str,
+        arguments: &Arguments,
+    ) -> Result<(), GrugError> {
+        let Some(on_function) = self
+            .mod_api
+            .entities
+            .get(entity_type)
+            .and_then(|entity| entity.on_functions.get(on_function_name))
+        else {
+            return Ok(());
+        };
+
+        let matches = on_function.arguments.len() == arguments.values.len()
+            && on_function
+                .arguments
+                .iter()
+                .zip(&arguments.values)
+                .all(|(argument, value)| value.matches(&argument.type_));
+
+        if matches {
+            return Ok(());
+        }
+
+        Err(GrugError::WrongArguments {
+            on_function: on_function_name.to_string(),
+            expected: on_function
+                .arguments
+                .iter()
+                .map(|argument| argument.type_.to_string())
+                .collect(),
+            given: arguments
+                .values
+                .iter()
+                .map(|value| value.type_name().to_string())
+                .collect(),
+        })
+    }
+
+    /// Handles an activation that happened while an `on_function` was running
+    fn reenter<F>(&self, action: String, arguments: &Arguments, command: F) -> Result<(), GrugError>
+    where
+        F: FnOnce(Vec<OwnedGrugValue>) -> Command,
+    {
+        let deferred = match self.reentrancy_policy {
+            ReentrancyPolicy::Error => None,
+            ReentrancyPolicy::Defer => arguments.to_owned_values(),
+        };
+
+        let Some(arguments) = deferred else {
+            return Err(GrugError::Reentrant { action });
+        };
+
+        self.commands.push(command(arguments));
+
+        Ok(())
+    }
+
+    /// Every mod in the mods folder with its `about.json`, sorted by directory name
+    pub fn mods(&self) -> &[LoadedMod] {
+        &self.mods
+    }
+
+    /// The mods that aren't compatible with the game version, so the game can warn about them
+    ///
+    /// Always empty if no game version was given.
+    pub fn incompatible_mods(&self) -> Vec<&LoadedMod> {
+        self.mods
+            .iter()
+            .filter(|mod_| !mod_.compatibility.is_compatible())
+            .collect()
+    }
+
+    /// The profile deciding which mods are enabled and their load order
+    pub fn profile(&self) -> &ModProfile {
+        &self.profile
+    }
+
+    /// Switches to another profile, which takes effect on the next activation
+    ///
+    /// Keeps the current profile if the dependencies of the new one can't be resolved.
+    pub fn set_profile(&mut self, profile: ModProfile) -> Result<(), GrugError> {
+        let old_profile = std::mem::replace(&mut self.profile, profile);
+
+        match self.resolve_load_order() {
+            Ok(load_order) => {
+                self.load_order = load_order;
+                self.clear_file_cache();
+                Ok(())
+            }
+            Err(error) => {
+                self.profile = old_profile;
+                Err(error)
+            }
+        }
+    }
+
+    /// Directory names of the active mods in the order their files are run
+    ///
+    /// Mods come after their dependencies, and otherwise follow the profile.
+    pub fn load_order(&self) -> &[String] {
+        &self.load_order
+    }
+
+    /// The mod API grug was initialized with, including reloaded descriptions
+    pub fn mod_api(&self) -> &ModAPI {
+        &self.mod_api
+    }
+
+    /// Reloads the descriptions of the mod API, for tools like `docs` that show them while the game runs
+    ///
+    /// Parses the mod API file again, or merges the fragments again if grug was built with
+    /// `GrugBuilder::from_mod_api_dir`. A mod API that isn't JSON is converted again, see `GrugBuilder::new`.
+    ///
+    /// This only reloads part of the mod API. grug can only read its mod API when it's initialized,
+    /// so any other change, like adding an `on_function` or changing an argument,
+    /// returns `ModAPIReload::RestartRequired` and keeps the current mod API.
+    /// The current mod API is also kept when anything fails.
+    pub fn reload_mod_api(&mut self) -> Result<ModAPIReload, GrugError> {
+        // Read before loading, so a change during the reload gets picked up next time
+        let modified = modified_time(self.watched_mod_api_path());
+        let mod_api = match &self.mod_api_source {
+            Some(mod_api_source) => ModAPI::load_file_or_dir(mod_api_source)?,
+            None => ModAPI::load(&self.mod_api_path)?,
+        };
+
+        mod_api
+            .validate()
+            .map_err(|problems| GrugError::InvalidModAPI {
+                path: self.mod_api_path.clone(),
+                problems,
+            })?;
+
+        let changes = needing_a_restart(&self.mod_api, &mod_api);
+        if !changes.is_empty() {
+            self.mod_api_modified = modified;
+            return Ok(ModAPIReload::RestartRequired { changes });
+        }
+
+        if self.mod_api_source.is_some() {
+            mod_api.save(&self.mod_api_path)?;
+        }
+
+        // Only descriptions differ, so the on_function indices and resource extensions still hold
+        self.mod_api = mod_api;
+        self.mod_api_modified = modified;
+
+        Ok(ModAPIReload::Reloaded)
+    }
+
+    /// The file or directory the mod API is converted from if there is one, otherwise the mod API file
+    fn watched_mod_api_path(&self) -> &Path {
+        self.mod_api_source.as_deref().unwrap_or(&self.mod_api_path)
+    }
+
+    /// Calls `reload_mod_api` if the mod API file, or a fragment, was modified since it was last loaded,
+    /// and returns `ModAPIReload::Unchanged` otherwise
+    ///
+    /// Call this every frame to watch the mod API while working on it.
+    /// A failed reload or a required restart is only reported once, until the file is modified again.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use grug_rs::{Arguments, Grug, ModAPIReload};
+    /// # fn main() -> Result<(), grug_rs::GrugError> {
+    /// let mut grug = Grug::new(None, "./mod_api.json", "./mods", "./mods_dll", 1000)?;
+    ///
+    /// loop {
+    ///     match grug.reload_mod_api_if_modified() {
+    ///         Ok(ModAPIReload::RestartRequired { changes }) => {
+    ///             for change in changes {
+    ///                 eprintln!("Restart to load the mod API change: {change}");
+    ///             }
+    ///         }
+    ///         Ok(_) => {}
+    ///         Err(error) => eprintln!("{error}"),
+    ///     }
+    ///
+    ///     grug.activate_on_function("World", "on_update", &mut Arguments::empty())?;
+    /// }
+    /// # }
+    /// ```
+    pub fn reload_mod_api_if_modified(&mut self) -> Result<ModAPIReload, GrugError> {
+        let modified = modified_time(self.watched_mod_api_path());
+        if modified == self.mod_api_modified {
+            return Ok(ModAPIReload::Unchanged);
+        }
+
+        self.mod_api_modified = modified;
+        self.reload_mod_api()
+    }
+
+    /// Sorts the active mods by the profile and then by their dependencies
+    fn resolve_load_order(&self) -> Result<Vec<String>, GrugError> {
+        let mut active: Vec<&LoadedMod> = self
+            .mods
+            .iter()
+            .filter(|mod_| self.is_mod_active(&mod_.dir_name))
+            .collect();
+
+        // `self.mods` is already sorted by name
+        active.sort_by_key(|mod_| self.profile.priority(&mod_.dir_name).unwrap_or(usize::MAX));
+
+        Ok(resolve_load_order(&active)?
+            .into_iter()
+            .map(|mod_| mod_.dir_name.clone())
+            .collect())
+    }
+
+    /// Whether the files of a mod should be run, based on its directory name
+    fn is_mod_active(&self, dir_name: &str) -> bool {
+        if !self.profile.is_enabled(dir_name) {
+            return false;
+        }
+
+        if self.compatibility_policy != CompatibilityPolicy::Skip {
+            return true;
+        }
+
+        self.mods
+            .iter()
+            .find(|mod_| mod_.dir_name == dir_name)
+            .is_none_or(|mod_| mod_.compatibility.is_compatible())
+    }
+
+    /// Gets a handle to the queue of deferred commands
+    ///
+    /// Game functions should push commands that spawn, despawn or activate entities
+    /// into this queue instead of doing so while an `on_function` is running.
+    pub fn commands(&self) -> CommandQueue {
+        self.commands.clone()
+    }
+
+    /// Applies every queued command, in the order they were pushed
+    ///
+    /// `Activate` commands are run by grug-rs, the other commands are passed to `handler`.
+    /// Commands pushed while flushing are left in the queue for the next flush.
+    ///
+    /// Call this after the activation that queued the commands has ended,
+    /// calling it while this thread is running an `on_function` errors with `GrugError::Reentrant`.
+    pub fn flush_commands<F: FnMut(Command)>(&self, mut handler: F) -> Result<(), GrugError> {
+        if self.is_in_on_function() {
+            return Err(GrugError::Reentrant {
+                action: "flush_commands".to_string(),
             });
         }
 
-        let index = *index.unwrap();
+        let mut commands = self.commands.take();
 
-        let files = self.get_files_by_entity_type(entity_name);
+        while let Some(command) = commands.pop_front() {
+            match command {
+                Command::Activate {
+                    entity_type,
+                    on_function,
+                    arguments,
+                } => {
+                    let mut arguments =
+                        Arguments::new(arguments.into_iter().map(GrugValue::from).collect());
 
-        for file in files {
-            unsafe { file.run_on_function(index, arguments.into_raw(), arguments.values.len())? };
+                    if let Err(error) =
+                        self.activate_on_function(entity_type, on_function, &mut arguments)
+                    {
+                        // Keep the commands that haven't been applied yet
+                        self.commands.requeue(commands);
+                        return Err(error);
+                    }
+                }
+                Command::ActivateEntity {
+                    entity,
+                    on_function,
+                    arguments,
+                } => {
+                    let mut arguments =
+                        Arguments::new(arguments.into_iter().map(GrugValue::from).collect());
+
+                    if let Err(error) =
+                        self.activate_entity_on_function(entity, on_function, &mut arguments)
+                    {
+                        // Keep the commands that haven't been applied yet
+                        self.commands.requeue(commands);
+                        return Err(error);
+                    }
+                }
+                command => handler(command),
+            }
         }
 
         Ok(())
     }
 
-    /// Get a list of grug files based on the name of an entity.
+    /// A snapshot of every mod directory and grug file grug has loaded
     ///
-    /// # Safety
-    /// This is only self because we want to ensure grug is initialized
-    pub fn get_files_by_entity_type<S: ToString>(&self, name: S) -> Vec<GrugFile> {
-        let name = name.to_string();
+    /// Reflects the mods as of the last time they were regenerated,
+    /// so it's empty before the first `regenerate_modified_mods` or `activate_on_function`.
+    ///
+    /// Waits for other threads to finish running their `on_function`, since they might regenerate.
+    pub fn mod_tree(&self) -> ModDir {
+        self.while_not_running(|| unsafe { self.copy_mod_tree() })
+    }
 
+    /// # Safety
+    /// No other thread may regenerate at the same time, see `while_not_running`
+    unsafe fn copy_mod_tree(&self) -> ModDir {
         #[allow(static_mut_refs)]
         let mods = unsafe { grug_mods }; // SAFETY: This implements the copy trait so it's safe to use
-        let mods = unsafe { from_raw_parts(mods.dirs, mods.dirs_size) };
-
-        let mut return_files = vec![];
-
-        for mod_ in mods.iter() {
-            let files = unsafe { from_raw_parts(mod_.files, mod_.files_size) };
-            for file in files {
-                let mod_entity_name = unsafe {
-                    CStr::from_ptr(file.entity_type)
-                        .to_string_lossy()
-                        .into_owned()
-                };
-                if mod_entity_name == name {
-                    return_files.push(GrugFile::new(*file));
-                }
+
+        unsafe { ModDir::from_raw(&mods, PathBuf::new(), &self.mod_api) }
+    }
+
+    /// Calls `f` while no other thread can regenerate or run an `on_function`
+    fn while_not_running<R>(&self, f: impl FnOnce() -> R) -> R {
+        // This thread already holds the lock
+        if self.is_in_on_function() {
+            return f();
+        }
+
+        let _running = self.running.lock().unwrap();
+        f()
+    }
+
+    /// Every grug file of the active mods that gets run, including files in sub-directories
+    ///
+    /// Sorted by `Grug::load_order` and then by path,
+    /// so the order doesn't depend on how grug stores them.
+    ///
+    /// Under `ConflictPolicy::LastWins` only the last definition of a conflicting entity is kept.
+    pub fn files(&self) -> Vec<GrugFile> {
+        let cache = self.file_cache();
+
+        cache
+            .running
+            .iter()
+            .map(|&i| cache.active[i].clone())
+            .collect()
+    }
+
+    /// Every entity that is defined by more than one active mod
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        self.file_cache().conflicts.clone()
+    }
+
+    /// The files of the active mods, rebuilt if grug's mod tree changed since they were copied
+    fn file_cache(&self) -> Arc<FileCache> {
+        self.while_not_running(|| {
+            let generation = mod_tree::generation();
+            let mut file_cache = self.file_cache.lock().unwrap();
+
+            if let Some(cache) = &*file_cache
+                && cache.generation == generation
+            {
+                return cache.clone();
             }
+
+            let cache = Arc::new(unsafe { self.build_file_cache(generation) });
+            *file_cache = Some(cache.clone());
+            cache
+        })
+    }
+
+    fn clear_file_cache(&mut self) {
+        *self.file_cache.get_mut().unwrap() = None;
+    }
+
+    /// # Safety
+    /// No other thread may regenerate at the same time, see `while_not_running`
+    unsafe fn build_file_cache(&self, generation: u64) -> FileCache {
+        let mut active: Vec<GrugFile> = unsafe { self.copy_mod_tree() }
+            .dirs()
+            .iter()
+            .filter(|mod_| self.is_mod_active(mod_.name()))
+            .flat_map(|mod_| mod_.walk_files())
+            .cloned()
+            .collect();
+
+        // Mods that were added after initializing go last
+        let positions: HashMap<&str, usize> = self
+            .load_order
+            .iter()
+            .enumerate()
+            .map(|(i, mod_name)| (mod_name.as_str(), i))
+            .collect();
+        let position = |file: &GrugFile| {
+            positions
+                .get(file.mod_name())
+                .copied()
+                .unwrap_or(usize::MAX)
+        };
+        active.sort_by(|a, b| {
+            (position(a), a.relative_path()).cmp(&(position(b), b.relative_path()))
+        });
+
+        let conflicts = find_conflicts(&active);
+
+        let overridden: HashSet<&Path> = match self.conflict_policy {
+            ConflictPolicy::LastWins => conflicts
+                .iter()
+                .flat_map(|conflict| {
+                    let (_, overridden) = conflict.definitions.split_last().unwrap();
+                    overridden
+                })
+                .map(|definition| definition.relative_path.as_path())
+                .collect(),
+            _ => HashSet::new(),
+        };
+
+        let running: Vec<usize> = (0..active.len())
+            .filter(|&i| !overridden.contains(active[i].relative_path()))
+            .collect();
+
+        let mut by_entity_type: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, &file) in running.iter().enumerate() {
+            by_entity_type
+                .entry(active[file].entity_type().to_string())
+                .or_default()
+                .push(i);
+        }
+
+        let mut by_entity = HashMap::new();
+        for (i, file) in active.iter().enumerate() {
+            by_entity.entry(file.entity().to_string()).or_insert(i);
+        }
+
+        FileCache {
+            generation,
+            active,
+            running,
+            by_entity_type,
+            by_entity,
+            conflicts,
         }
+    }
+
+    /// Gets the grug file of a single entity, like `"base_game:goblin"`
+    ///
+    /// Overridden entities can still be found, but disabled or skipped mods can't.
+    /// Errors with `GrugError::UnknownEntity` listing close matches if it doesn't exist.
+    pub fn get_file_by_entity<S: ToString>(&self, entity: S) -> Result<GrugFile, GrugError> {
+        self.file_cache().file_by_entity(entity.to_string())
+    }
+
+    /// Get a list of grug files based on the name of an entity.
+    ///
+    /// Looks through every sub-directory of every mod, in the order of `Grug::files`.
+    ///
+    /// # Safety
+    /// This is only self because we want to ensure grug is initialized
+    pub fn get_files_by_entity_type<S: ToString>(&self, name: S) -> Vec<GrugFile> {
+        let cache = self.file_cache();
 
-        return_files
+        cache
+            .by_entity_type
+            .get(&name.to_string())
+            .into_iter()
+            .flatten()
+            .map(|&i| cache.active[cache.running[i]].clone())
+            .collect()
     }
 }
 
 /// An opaque grug type
+#[cfg(feature = "runtime")]
 #[repr(C)]
 #[derive(Clone, Copy)]
 pub struct OpaqueGrugType {
     pub raw: *mut c_void,
 }
 
-pub struct GrugFile {
-    pub inner: grug_file,
-}
+#[cfg(all(test, feature = "runtime"))]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
 
-impl GrugFile {
-    pub fn new(file: grug_file) -> Self {
-        Self { inner: file }
-    }
+    use super::*;
 
-    /// # SAFETY
-    /// Will segfault if you put an invalid index.
-    ///
-    /// Assumes `arguments` is non-null.
-    pub unsafe fn run_on_function(
-        &self,
-        index: usize,
-        arguments: *mut *mut c_void,
-        arguments_len: usize,
-    ) -> Result<(), GrugError> {
-        let ptr = self.inner.on_fns as *mut unsafe extern "C" fn(*mut c_void);
-        let func = unsafe { from_raw_parts_mut(ptr, index + 1) }.last_mut();
+    const ENTITIES: [&str; 4] = ["base:goblin", "base:slime", "extra:gobbo", "extra:dragon"];
 
-        if func.is_none() {
-            // Ensure the function actually has a definition
-            return Err(GrugError::UndefinedFunction);
+    /// A grug that was never initialized, so grug's mod tree is still empty
+    fn uninitialized_grug() -> Grug {
+        Grug {
+            mod_api: ModAPI::builder().build(),
+            mod_api_path: PathBuf::new(),
+            mod_api_modified: None,
+            mod_api_source: None,
+            entities: HashMap::new(),
+            mods: vec![],
+            commands: CommandQueue::new(),
+            reentrancy_policy: ReentrancyPolicy::Error,
+            compatibility_policy: CompatibilityPolicy::Warn,
+            conflict_policy: ConflictPolicy::RunAll,
+            profile: ModProfile::default(),
+            load_order: vec![],
+            running: Mutex::new(()),
+            file_cache: Mutex::new(None),
         }
+    }
 
-        let globals = unsafe { alloc(Layout::array::<u8>(self.inner.globals_size).unwrap()) };
-        unsafe { (self.inner.init_globals_fn.unwrap())(globals as *mut c_void, 0) };
-
-        let func = func.unwrap() as *mut unsafe extern "C" fn(*mut c_void);
-
-        unsafe {
-            let args = from_raw_parts(arguments, arguments_len);
-            seq!(N in 1..3 {
-                match arguments_len {
-                    0 => (*func)(null_mut()),
-                    #(N => {
-                        seq!(M in 0..N {
-                            let func = func as *mut unsafe extern "C" fn(*mut c_void, #(OpaqueGrugType,)*);
-                            (*func)(globals as *mut c_void, #(*(args[M] as *mut _),)*);
-                        });
-                    },)*
-                    _ => panic!("Too many arguments, either report this or refactor."),
-                }
-            })
-        }
+    #[test]
+    fn reentrancy_is_per_thread() {
+        let grug = uninitialized_grug();
 
-        Ok(())
+        let guard = OnFunctionGuard::enter(&grug).unwrap();
+        assert!(grug.is_in_on_function());
+        assert!(OnFunctionGuard::enter(&grug).is_none());
+        // Doesn't wait for its own lock
+        assert!(grug.files().is_empty());
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| assert!(!grug.is_in_on_function()));
+        });
+
+        drop(guard);
+        assert!(!grug.is_in_on_function());
+    }
+
+    #[test]
+    fn other_threads_wait_instead_of_erroring() {
+        let grug = uninitialized_grug();
+        let released = AtomicBool::new(false);
+        let (entered, wait_for_enter) = std::sync::mpsc::channel();
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let _guard = OnFunctionGuard::enter(&grug).unwrap();
+                entered.send(()).unwrap();
+                std::thread::sleep(std::time::Duration::from_millis(50));
+                released.store(true, Ordering::SeqCst);
+            });
+
+            wait_for_enter.recv().unwrap();
+
+            let guard = OnFunctionGuard::enter(&grug);
+            assert!(guard.is_some());
+            assert!(released.load(Ordering::SeqCst));
+        });
+
+        released.store(false, Ordering::SeqCst);
+        std::thread::scope(|scope| {
+            let guard = OnFunctionGuard::enter(&grug).unwrap();
+
+            let reader = scope.spawn(|| {
+                grug.mod_tree();
+                grug.files();
+                released.load(Ordering::SeqCst)
+            });
+
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            released.store(true, Ordering::SeqCst);
+            drop(guard);
+
+            assert!(reader.join().unwrap());
+        });
+    }
+
+    #[test]
+    fn close_matches_with_or_without_mod() {
+        assert_eq!(
+            close_matches("goblin", ENTITIES),
+            ["base:goblin", "extra:gobbo"]
+        );
+        assert_eq!(
+            close_matches("base:gobln", ENTITIES),
+            ["base:goblin", "extra:gobbo"]
+        );
+        assert_eq!(close_matches("extra:slime", ENTITIES), ["base:slime"]);
+    }
+
+    #[test]
+    fn close_matches_closest_first() {
+        assert_eq!(
+            close_matches("gobbo", ENTITIES),
+            ["extra:gobbo", "base:goblin"]
+        );
+    }
+
+    #[test]
+    fn close_matches_nothing_alike() {
+        assert!(close_matches("spaceship", ENTITIES).is_empty());
+        assert!(close_matches("goblin", []).is_empty());
+    }
+
+    #[test]
+    fn close_matches_at_most_5() {
+        let candidates = ["a:aa", "a:ab", "a:ac", "a:ad", "a:ae", "a:af"];
+
+        assert_eq!(
+            close_matches("aa", candidates),
+            ["a:aa", "a:ab", "a:ac", "a:ad", "a:ae"]
+        );
+    }
+
+    #[test]
+    fn close_matches_without_duplicates() {
+        assert_eq!(close_matches("slime", ["a:slime", "a:slime"]), ["a:slime"]);
+    }
+
+    #[test]
+    fn did_you_mean_lists_suggestions() {
+        assert_eq!(did_you_mean(&[]), "");
+        assert_eq!(
+            did_you_mean(&["a:b".to_string(), "c:d".to_string()]),
+            ", did you mean: `a:b`, `c:d`"
+        );
     }
 }
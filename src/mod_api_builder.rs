@@ -0,0 +1,199 @@
+use crate::mod_api_type::{Argument, Entity, GameFunction, GrugType, ModAPI};
+
+/// A game function registered with `#[game_function(description = "...")]`
+#[derive(Debug)]
+pub struct RegisteredGameFunction {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// The name, grug type, and resource extension or entity type of every argument
+    pub arguments: &'static [(&'static str, &'static str, &'static str)],
+    pub return_type: Option<&'static str>,
+}
+
+inventory::collect!(RegisteredGameFunction);
+
+/// A game function implemented with `#[game_function]`, with or without a `description`
+///
+/// `GrugBuilder` checks that every game function of the mod API is one of these.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct ImplementedGameFunction {
+    pub name: &'static str,
+}
+
+inventory::collect!(ImplementedGameFunction);
+
+impl RegisteredGameFunction {
+    /// Every registered game function, sorted by name
+    pub fn all() -> Vec<&'static RegisteredGameFunction> {
+        let mut game_functions: Vec<_> = inventory::iter::<RegisteredGameFunction>
+            .into_iter()
+            .collect();
+        game_functions.sort_by_key(|game_function| game_function.name);
+        game_functions
+    }
+
+    pub fn to_game_function(&self) -> GameFunction {
+        GameFunction {
+            description: self.description.to_string(),
+            return_type: self.return_type.map(GrugType::from),
+            arguments: self
+                .arguments
+                .iter()
+                .map(|&(name, type_, detail)| Argument {
+                    name: name.to_string(),
+                    type_: match GrugType::from(type_) {
+                        GrugType::Resource { .. } => GrugType::Resource {
+                            extension: detail.to_string(),
+                        },
+                        GrugType::Entity { .. } => GrugType::Entity {
+                            entity_type: detail.to_string(),
+                        },
+                        type_ => type_,
+                    },
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Builds a mod API, see `ModAPI::builder`
+///
+/// It can also fill in `game_functions` from the ones registered on the Rust side.
+///
+/// # Example
+/// ```no_run
+/// use grug_rs::{ModApiBuilder, mod_api_type::ModAPI};
+///
+/// # fn main() -> Result<(), grug_rs::GrugError> {
+/// // Keep the entities and replace the game functions that are registered
+/// ModApiBuilder::from_mod_api(ModAPI::load("./mod_api.json")?)
+///     .registered_game_functions()
+///     .build()
+///     .save("./mod_api.json")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ModApiBuilder {
+    mod_api: ModAPI,
+    /// The entity `on_function` adds to
+    entity: Option<String>,
+}
+
+impl ModApiBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts from an existing mod API, like one loaded with `ModAPI::load`
+    pub fn from_mod_api(mod_api: ModAPI) -> Self {
+        Self {
+            mod_api,
+            entity: None,
+        }
+    }
+
+    /// Adds an entity, replacing one with the same name in place.
+    /// Following calls to `on_function` add to this entity.
+    pub fn entity<S1: ToString, S2: ToString>(mut self, name: S1, description: S2) -> Self {
+        let name = name.to_string();
+        let entity = Entity::new(description);
+
+        match self.mod_api.entities.get_mut(&name) {
+            Some(existing) => *existing = entity,
+            None => {
+                self.mod_api.entities.insert(name.clone(), entity);
+            }
+        }
+
+        self.entity = Some(name);
+        self
+    }
+
+    /// Adds an `on_function` to the entity added last
+    ///
+    /// # Panics
+    /// If no entity has been added with `entity` yet
+    pub fn on_function<S: ToString>(mut self, name: S, on_function: GameFunction) -> Self {
+        let entity = self
+            .entity
+            .as_ref()
+            .and_then(|entity| self.mod_api.entities.get_mut(entity))
+            .expect("`on_function` needs an entity, add one with `entity` first");
+
+        entity.on_functions.insert(name.to_string(), on_function);
+        self
+    }
+
+    /// Adds a game function, replacing one with the same name in place
+    pub fn game_function<S: ToString>(mut self, name: S, game_function: GameFunction) -> Self {
+        let name = name.to_string();
+
+        match self.mod_api.game_functions.get_mut(&name) {
+            Some(existing) => *existing = game_function,
+            None => {
+                self.mod_api.game_functions.insert(name, game_function);
+            }
+        }
+
+        self
+    }
+
+    /// Adds every game function registered with `#[game_function(description = "...")]`
+    ///
+    /// Game functions that already exist are replaced in place, new ones are added sorted by name.
+    ///
+    /// # Example
+    /// ```
+    /// use grug_rs::{ModApiBuilder, mod_api_type::GrugType};
+    /// use grug_rs_proc_macro::game_function;
+    ///
+    /// #[game_function(description = "Adds two numbers")]
+    /// fn add(a: i32, b: i32) -> i32 {
+    ///     a + b
+    /// }
+    ///
+    /// #[game_function(description = "Prints a string with a new line")]
+    /// fn println(message: String) {
+    ///     println!("{message}");
+    /// }
+    ///
+    /// fn main() {
+    ///     let mod_api = ModApiBuilder::new().registered_game_functions().build();
+    ///     assert_eq!(
+    ///         mod_api.game_functions.keys().collect::<Vec<_>>(),
+    ///         ["add", "println"]
+    ///     );
+    ///
+    ///     let add = &mod_api.game_functions["add"];
+    ///     assert_eq!(add.description, "Adds two numbers");
+    ///     assert_eq!(add.arguments[1].to_string(), "b: i32");
+    ///     assert_eq!(add.return_type, Some(GrugType::I32));
+    ///
+    ///     let println = &mod_api.game_functions["println"];
+    ///     assert_eq!(println.arguments[0].to_string(), "message: string");
+    ///     assert_eq!(println.return_type, None);
+    /// }
+    /// ```
+    ///
+    /// Resource arguments need their extension, so this doesn't compile:
+    /// ```compile_fail
+    /// use grug_rs::resource::Resource;
+    /// use grug_rs_proc_macro::game_function;
+    ///
+    /// #[game_function(description = "Draws a sprite")]
+    /// fn draw(sprite: Resource) {}
+    /// ```
+    pub fn registered_game_functions(self) -> Self {
+        RegisteredGameFunction::all()
+            .into_iter()
+            .fold(self, |builder, registered| {
+                builder.game_function(registered.name, registered.to_game_function())
+            })
+    }
+
+    pub fn build(self) -> ModAPI {
+        self.mod_api
+    }
+}
@@ -0,0 +1,486 @@
+use linked_hash_map::LinkedHashMap;
+
+use crate::mod_api_type::{Argument, GameFunction, GrugType, ModAPI, json_key};
+
+/// A change between two versions of a mod API, see `ModAPI::diff`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// The JSON path of what changed, like `entities.World.on_functions.on_update`
+    pub path: String,
+    pub kind: ChangeKind,
+    /// Whether mods made for the old mod API can fail to compile against the new one
+    pub breaking: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    /// What was kept is in a different order, which only matters to a running grug
+    Reordered,
+    Description {
+        old: String,
+        new: String,
+    },
+    Arguments {
+        old: Vec<Argument>,
+        new: Vec<Argument>,
+    },
+    ReturnType {
+        old: Option<GrugType>,
+        new: Option<GrugType>,
+    },
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = &self.path;
+
+        match &self.kind {
+            ChangeKind::Added => write!(f, "`{path}` was added"),
+            ChangeKind::Removed => write!(f, "`{path}` was removed"),
+            ChangeKind::Reordered => write!(f, "`{path}` were reordered"),
+            ChangeKind::Description { .. } => write!(f, "`{path}` changed"),
+            ChangeKind::Arguments { old, new } => write!(
+                f,
+                "`{path}` changed from `({})` to `({})`",
+                argument_list(old),
+                argument_list(new)
+            ),
+            ChangeKind::ReturnType { old, new } => write!(
+                f,
+                "`{path}` changed from `{}` to `{}`",
+                return_type_name(old),
+                return_type_name(new)
+            ),
+        }
+    }
+}
+
+fn argument_list(arguments: &[Argument]) -> String {
+    arguments
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn return_type_name(return_type: &Option<GrugType>) -> String {
+    return_type
+        .as_ref()
+        .map_or("nothing".to_string(), ToString::to_string)
+}
+
+/// The changes from `old` to `new` that a running grug can't pick up, see `Grug::reload_mod_api`
+///
+/// grug reads its mod API once in `grug_init` and compiles it into the mods,
+/// so that's everything but descriptions.
+#[cfg(feature = "runtime")]
+pub(crate) fn needing_a_restart(old: &ModAPI, new: &ModAPI) -> Vec<Change> {
+    diff(old, new)
+        .into_iter()
+        .filter(|change| !matches!(change.kind, ChangeKind::Description { .. }))
+        .collect()
+}
+
+pub(crate) fn diff(old: &ModAPI, new: &ModAPI) -> Vec<Change> {
+    let mut changes = vec![];
+
+    key_changes("entities", &old.entities, &new.entities, true, &mut changes);
+
+    for (name, entity) in &old.entities {
+        let Some(new_entity) = new.entities.get(name) else {
+            continue;
+        };
+        let path = format!("entities{}", json_key(name));
+
+        if entity.description != new_entity.description {
+            changes.push(Change {
+                path: format!("{path}.description"),
+                kind: ChangeKind::Description {
+                    old: entity.description.clone(),
+                    new: new_entity.description.clone(),
+                },
+                breaking: false,
+            });
+        }
+
+        let path = format!("{path}.on_functions");
+        key_changes(
+            &path,
+            &entity.on_functions,
+            &new_entity.on_functions,
+            true,
+            &mut changes,
+        );
+
+        for (name, on_function) in &entity.on_functions {
+            if let Some(new_on_function) = new_entity.on_functions.get(name) {
+                let path = format!("{path}{}", json_key(name));
+                function_changes(&path, false, on_function, new_on_function, &mut changes);
+            }
+        }
+    }
+
+    // grug looks game functions up by name, so their order doesn't matter
+    key_changes(
+        "game_functions",
+        &old.game_functions,
+        &new.game_functions,
+        false,
+        &mut changes,
+    );
+
+    for (name, game_function) in &old.game_functions {
+        if let Some(new_game_function) = new.game_functions.get(name) {
+            let path = format!("game_functions{}", json_key(name));
+            function_changes(&path, true, game_function, new_game_function, &mut changes);
+        }
+    }
+
+    changes
+}
+
+/// Pushes the keys that were added or removed, and whether the rest were reordered
+///
+/// Removing breaks the mods that use it, adding and reordering doesn't.
+fn key_changes<V>(
+    path: &str,
+    old: &LinkedHashMap<String, V>,
+    new: &LinkedHashMap<String, V>,
+    ordered: bool,
+    changes: &mut Vec<Change>,
+) {
+    for name in old.keys().filter(|name| !new.contains_key(*name)) {
+        changes.push(Change {
+            path: format!("{path}{}", json_key(name)),
+            kind: ChangeKind::Removed,
+            breaking: true,
+        });
+    }
+    for name in new.keys().filter(|name| !old.contains_key(*name)) {
+        changes.push(Change {
+            path: format!("{path}{}", json_key(name)),
+            kind: ChangeKind::Added,
+            breaking: false,
+        });
+    }
+
+    let kept = |map: &LinkedHashMap<String, V>, other: &LinkedHashMap<String, V>| -> Vec<String> {
+        map.keys()
+            .filter(|name| other.contains_key(*name))
+            .cloned()
+            .collect()
+    };
+
+    if ordered && kept(old, new) != kept(new, old) {
+        changes.push(Change {
+            path: path.to_string(),
+            kind: ChangeKind::Reordered,
+            breaking: false,
+        });
+    }
+}
+
+/// Pushes the changes to the description, return type and arguments of an `on_function` or game function
+fn function_changes(
+    path: &str,
+    is_game_function: bool,
+    old: &GameFunction,
+    new: &GameFunction,
+    changes: &mut Vec<Change>,
+) {
+    if old.description != new.description {
+        changes.push(Change {
+            path: format!("{path}.description"),
+            kind: ChangeKind::Description {
+                old: old.description.clone(),
+                new: new.description.clone(),
+            },
+            breaking: false,
+        });
+    }
+
+    if old.return_type != new.return_type {
+        changes.push(Change {
+            path: format!("{path}.return_type"),
+            kind: ChangeKind::ReturnType {
+                old: old.return_type.clone(),
+                new: new.return_type.clone(),
+            },
+            breaking: true,
+        });
+    }
+
+    if old.arguments != new.arguments {
+        // Mods call game functions without naming the arguments,
+        // but have to name the arguments of `on_functions` they define exactly like the mod API
+        let breaking = !is_game_function
+            || old.arguments.len() != new.arguments.len()
+            || old
+                .arguments
+                .iter()
+                .zip(&new.arguments)
+                .any(|(old, new)| old.type_ != new.type_);
+
+        changes.push(Change {
+            path: format!("{path}.arguments"),
+            kind: ChangeKind::Arguments {
+                old: old.arguments.clone(),
+                new: new.arguments.clone(),
+            },
+            breaking,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mod_api_type::Entity;
+
+    fn world() -> Entity {
+        let mut world = Entity::new("The world");
+        world.on_functions.insert(
+            "on_update".to_string(),
+            GameFunction::new("Called every tick"),
+        );
+        world.on_functions.insert(
+            "on_hit".to_string(),
+            GameFunction::new("Called when hit").argument("damage", "i32"),
+        );
+        world
+    }
+
+    fn mod_api() -> ModAPI {
+        ModAPI::builder()
+            .game_function(
+                "print",
+                GameFunction::new("Prints").argument("msg", "string"),
+            )
+            .build()
+    }
+
+    fn with_world(mut mod_api: ModAPI, world: Entity) -> ModAPI {
+        mod_api.entities.insert("World".to_string(), world);
+        mod_api
+    }
+
+    fn summary(changes: &[Change]) -> Vec<(String, bool)> {
+        changes
+            .iter()
+            .map(|change| (change.to_string(), change.breaking))
+            .collect()
+    }
+
+    #[test]
+    fn no_changes() {
+        let mod_api = with_world(mod_api(), world());
+
+        assert!(diff(&mod_api, &mod_api.clone()).is_empty());
+    }
+
+    #[test]
+    fn adding_isnt_breaking_and_removing_is() {
+        let old = with_world(mod_api(), world());
+        let mut new = with_world(ModAPI::default(), world());
+        new.game_functions.insert(
+            "spawn".to_string(),
+            GameFunction::new("Spawns").return_type("id"),
+        );
+        new.entities
+            .insert("Enemy".to_string(), Entity::new("An enemy"));
+
+        assert_eq!(
+            summary(&diff(&old, &new)),
+            [
+                ("`entities.Enemy` was added".to_string(), false),
+                ("`game_functions.print` was removed".to_string(), true),
+                ("`game_functions.spawn` was added".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn reordering_on_functions_isnt_breaking() {
+        let old = with_world(mod_api(), world());
+        let mut world = world();
+        let update = world.on_functions.remove("on_update").unwrap();
+        world.on_functions.insert("on_update".to_string(), update);
+        let new = with_world(mod_api(), world);
+
+        let changes = diff(&old, &new);
+        assert_eq!(
+            summary(&changes),
+            [(
+                "`entities.World.on_functions` were reordered".to_string(),
+                false
+            )]
+        );
+        assert_eq!(changes[0].kind, ChangeKind::Reordered);
+    }
+
+    #[test]
+    fn reordering_game_functions_is_no_change() {
+        let old = ModAPI::builder()
+            .game_function("a", GameFunction::new("A"))
+            .game_function("b", GameFunction::new("B"))
+            .build();
+        let new = ModAPI::builder()
+            .game_function("b", GameFunction::new("B"))
+            .game_function("a", GameFunction::new("A"))
+            .build();
+
+        assert!(diff(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn renaming_a_game_function_argument_isnt_breaking() {
+        let old = mod_api();
+        let new = ModAPI::builder()
+            .game_function(
+                "print",
+                GameFunction::new("Prints").argument("message", "string"),
+            )
+            .build();
+
+        assert_eq!(
+            summary(&diff(&old, &new)),
+            [(
+                "`game_functions.print.arguments` changed from `(msg: string)` to `(message: string)`"
+                    .to_string(),
+                false
+            )]
+        );
+    }
+
+    #[test]
+    fn renaming_an_on_function_argument_is_breaking() {
+        let old = with_world(mod_api(), world());
+        let mut world = world();
+        world.on_functions["on_hit"].arguments[0].name = "amount".to_string();
+        let new = with_world(mod_api(), world);
+
+        assert_eq!(
+            summary(&diff(&old, &new)),
+            [(
+                "`entities.World.on_functions.on_hit.arguments` changed from `(damage: i32)` to `(amount: i32)`"
+                    .to_string(),
+                true
+            )]
+        );
+    }
+
+    #[test]
+    fn changing_argument_types_or_counts_is_breaking() {
+        let old = mod_api();
+        let retyped = ModAPI::builder()
+            .game_function("print", GameFunction::new("Prints").argument("msg", "i32"))
+            .build();
+        let extended = ModAPI::builder()
+            .game_function(
+                "print",
+                GameFunction::new("Prints")
+                    .argument("msg", "string")
+                    .argument("color", "i32"),
+            )
+            .build();
+
+        assert!(diff(&old, &retyped)[0].breaking);
+        assert!(diff(&old, &extended)[0].breaking);
+    }
+
+    #[test]
+    fn changing_a_return_type_is_breaking() {
+        let old = mod_api();
+        let new = ModAPI::builder()
+            .game_function(
+                "print",
+                GameFunction::new("Prints")
+                    .argument("msg", "string")
+                    .return_type("bool"),
+            )
+            .build();
+
+        let changes = diff(&old, &new);
+        assert_eq!(
+            summary(&changes),
+            [(
+                "`game_functions.print.return_type` changed from `nothing` to `bool`".to_string(),
+                true
+            )]
+        );
+        assert_eq!(
+            changes[0].kind,
+            ChangeKind::ReturnType {
+                old: None,
+                new: Some(GrugType::Bool)
+            }
+        );
+    }
+
+    #[test]
+    fn descriptions_arent_breaking() {
+        let old = with_world(mod_api(), world());
+        let mut world = world();
+        world.description = "The whole world".to_string();
+        world.on_functions["on_update"].description = "Called every frame".to_string();
+        let new = with_world(mod_api(), world);
+
+        assert_eq!(
+            summary(&diff(&old, &new)),
+            [
+                ("`entities.World.description` changed".to_string(), false),
+                (
+                    "`entities.World.on_functions.on_update.description` changed".to_string(),
+                    false
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "runtime")]
+    fn only_descriptions_can_change_without_a_restart() {
+        let old = with_world(mod_api(), world());
+
+        let mut described = world();
+        described.description = "The whole world".to_string();
+        described.on_functions["on_hit"].description = "Called when damaged".to_string();
+        assert!(needing_a_restart(&old, &with_world(mod_api(), described)).is_empty());
+
+        let mut renamed = world();
+        renamed.on_functions["on_hit"].arguments[0].name = "amount".to_string();
+        let mut retyped = world();
+        retyped.on_functions["on_hit"].arguments[0].type_ = GrugType::F32;
+        let returning = ModAPI::builder()
+            .game_function(
+                "print",
+                GameFunction::new("Prints")
+                    .argument("msg", "string")
+                    .return_type("bool"),
+            )
+            .build();
+
+        for (new, change) in [
+            (
+                with_world(mod_api(), renamed),
+                "`entities.World.on_functions.on_hit.arguments` changed from `(damage: i32)` to `(amount: i32)`",
+            ),
+            (
+                with_world(mod_api(), retyped),
+                "`entities.World.on_functions.on_hit.arguments` changed from `(damage: i32)` to `(damage: f32)`",
+            ),
+            (
+                with_world(returning, world()),
+                "`game_functions.print.return_type` changed from `nothing` to `bool`",
+            ),
+        ] {
+            let changes: Vec<String> = needing_a_restart(&old, &new)
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            assert_eq!(changes, [change]);
+        }
+    }
+}
@@ -1,28 +1,1181 @@
+#[cfg(feature = "schemars")]
+use std::collections::BTreeMap;
+use std::{
+    collections::HashMap,
+    fs::{read_dir, read_to_string, write},
+    path::{Path, PathBuf},
+};
+
 use linked_hash_map::LinkedHashMap;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{from_str, to_string_pretty};
+
+use crate::{
+    GrugError, ModApiBuilder,
+    mod_api_diff::{self, Change},
+};
 
-#[derive(Serialize, Deserialize, Debug)]
+// The `schemars` attributes make the schema only accept what grug does, see `schema::mod_api`
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(deny_unknown_fields))]
 pub struct ModAPI {
+    /// The entity types mods can define, by name
+    #[cfg_attr(feature = "schemars", schemars(with = "BTreeMap<String, Entity>"))]
     pub entities: LinkedHashMap<String, Entity>,
+    /// The functions mods can call, by name
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(with = "BTreeMap<String, GameFunction>")
+    )]
     pub game_functions: LinkedHashMap<String, GameFunction>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// A file of a mod API that's split across a directory, see `ModAPI::load_dir`
+///
+/// Unlike a whole mod API, either can be left out.
+#[derive(Deserialize)]
+struct Fragment {
+    #[serde(default)]
+    entities: LinkedHashMap<String, Entity>,
+    #[serde(default)]
+    game_functions: LinkedHashMap<String, GameFunction>,
+}
+
+impl From<Fragment> for ModAPI {
+    fn from(fragment: Fragment) -> Self {
+        Self {
+            entities: fragment.entities,
+            game_functions: fragment.game_functions,
+        }
+    }
+}
+
+impl ModAPI {
+    /// Builds a mod API in Rust
+    ///
+    /// # Example
+    /// ```
+    /// use grug_rs::mod_api_type::{GameFunction, ModAPI};
+    ///
+    /// let mod_api = ModAPI::builder()
+    ///     .entity("World", "Let's print in here")
+    ///     .on_function("on_update", GameFunction::new("Called every tick"))
+    ///     .game_function(
+    ///         "println",
+    ///         GameFunction::new("Prints a string with a new line").argument("msg", "string"),
+    ///     )
+    ///     .build();
+    ///
+    /// assert!(mod_api.entities["World"].on_functions.contains_key("on_update"));
+    /// ```
+    pub fn builder() -> ModApiBuilder {
+        ModApiBuilder::new()
+    }
+
+    /// Reads and deserializes a `mod_api.json`
+    ///
+    /// With the `toml` or `yaml` feature, `.toml`, `.yaml` and `.yml` files are read in those formats,
+    /// so the mod API can have comments. Without them those files error with `GrugError::ModAPIFeature`,
+    /// and any other extension errors with `GrugError::ModAPIFormat`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, GrugError> {
+        parse_file(path.as_ref())
+    }
+
+    /// Loads a directory with `load_dir` and a file with `load`
+    #[cfg(feature = "runtime")]
+    pub(crate) fn load_file_or_dir(path: &Path) -> Result<Self, GrugError> {
+        if path.is_dir() {
+            Self::load_dir(path)
+        } else {
+            Self::load(path)
+        }
+    }
+
+    /// Reads every fragment in a directory, sorted by file name, and merges them with `merge`
+    ///
+    /// Fragments are `*.json`, `*.toml`, `*.yaml` and `*.yml` files, see `load`.
+    /// Other files are ignored. Unlike a whole mod API, a fragment can leave out
+    /// `entities` or `game_functions`.
+    ///
+    /// Errors with `GrugError::ModAPIConflicts` listing every entity or game function
+    /// that is defined by more than one fragment.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use grug_rs::mod_api_type::ModAPI;
+    ///
+    /// # fn main() -> Result<(), grug_rs::GrugError> {
+    /// // Like `mod_api/enemies.json`, `mod_api/world.json` and `mod_api/game_functions.json`
+    /// let mod_api = ModAPI::load_dir("./mod_api")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn load_dir<P: AsRef<Path>>(dir: P) -> Result<Self, GrugError> {
+        let dir = dir.as_ref();
+
+        let mut paths = fragment_paths(dir).map_err(|x| GrugError::ReadModAPI {
+            path: dir.to_path_buf(),
+            error: x.to_string(),
+        })?;
+        paths.sort();
+
+        let mut mod_api = ModAPI::default();
+        // Which fragment defined every entity and game function
+        let mut owners: HashMap<String, PathBuf> = HashMap::new();
+        let mut conflicts = vec![];
+
+        for path in paths {
+            let fragment: Fragment = parse_file(&path)?;
+
+            mod_api.merge_with(fragment.into(), |key| match owners.get(&key) {
+                Some(owner) => conflicts.push(format!(
+                    "`{key}` is defined by both `{}` and `{}`",
+                    owner.display(),
+                    path.display()
+                )),
+                None => {
+                    owners.insert(key, path.clone());
+                }
+            });
+        }
+
+        if conflicts.is_empty() {
+            Ok(mod_api)
+        } else {
+            Err(GrugError::ModAPIConflicts { conflicts })
+        }
+    }
+
+    /// Adds the entities and game functions of another mod API after the ones of this one
+    ///
+    /// Nothing is merged if both define the same entity or game function,
+    /// which errors with `GrugError::ModAPIConflicts`.
+    ///
+    /// # Example
+    /// ```
+    /// use grug_rs::mod_api_type::{GameFunction, ModAPI};
+    ///
+    /// let mut mod_api = ModAPI::builder()
+    ///     .entity("World", "The world")
+    ///     .on_function("on_update", GameFunction::new("Called every tick"))
+    ///     .build();
+    ///
+    /// let enemies = ModAPI::builder()
+    ///     .entity("Enemy", "Something to fight")
+    ///     .on_function("on_update", GameFunction::new("Called every tick"))
+    ///     .build();
+    ///
+    /// mod_api.merge(enemies.clone()).unwrap();
+    /// assert_eq!(mod_api.entities.keys().collect::<Vec<_>>(), ["World", "Enemy"]);
+    ///
+    /// assert!(mod_api.merge(enemies).is_err());
+    /// ```
+    pub fn merge(&mut self, other: ModAPI) -> Result<(), GrugError> {
+        let conflicts: Vec<String> = other
+            .entities
+            .keys()
+            .filter(|name| self.entities.contains_key(*name))
+            .map(|name| format!("entities{}", json_key(name)))
+            .chain(
+                other
+                    .game_functions
+                    .keys()
+                    .filter(|name| self.game_functions.contains_key(*name))
+                    .map(|name| format!("game_functions{}", json_key(name))),
+            )
+            .map(|key| format!("`{key}` is defined by both mod APIs"))
+            .collect();
+
+        if !conflicts.is_empty() {
+            return Err(GrugError::ModAPIConflicts { conflicts });
+        }
+
+        self.merge_with(other, |_| {});
+        Ok(())
+    }
+
+    /// Merges `other` in, calling `defined` with the JSON path of every entity and game function of it
+    ///
+    /// The ones this mod API already has are kept.
+    fn merge_with<F: FnMut(String)>(&mut self, other: ModAPI, mut defined: F) {
+        for (name, entity) in other.entities {
+            defined(format!("entities{}", json_key(&name)));
+
+            if !self.entities.contains_key(&name) {
+                self.entities.insert(name, entity);
+            }
+        }
+
+        for (name, game_function) in other.game_functions {
+            defined(format!("game_functions{}", json_key(&name)));
+
+            if !self.game_functions.contains_key(&name) {
+                self.game_functions.insert(name, game_function);
+            }
+        }
+    }
+
+    /// Writes the mod API as pretty printed json
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), GrugError> {
+        let path = path.as_ref();
+
+        write(path, to_string_pretty(self).unwrap()).map_err(|x| GrugError::WriteModAPI {
+            path: path.to_path_buf(),
+            error: x.to_string(),
+        })
+    }
+
+    /// Checks the mod API for everything grug would reject, returning every problem
+    ///
+    /// Each problem starts with the JSON path it's about.
+    ///
+    /// # Example
+    /// ```
+    /// use grug_rs::mod_api_type::{GameFunction, ModAPI};
+    ///
+    /// let mod_api = ModAPI::builder()
+    ///     .entity("World", "The world")
+    ///     .on_function("update", GameFunction::new("Called every tick"))
+    ///     .game_function("print", GameFunction::new("Prints").argument("msg", "str"))
+    ///     .build();
+    ///
+    /// assert_eq!(
+    ///     mod_api.validate().unwrap_err(),
+    ///     [
+    ///         "`entities.World.on_functions.update`: on_function names must start with `on_`",
+    ///         "`game_functions.print.arguments[0].type`: unknown type `str`, custom types must be in PascalCase",
+    ///     ]
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut problems = vec![];
+
+        for (name, entity) in &self.entities {
+            let path = format!("entities{}", json_key(name));
+
+            if !is_pascal_case(name) {
+                problems.push(format!("`{path}`: entity names must be in PascalCase"));
+            }
+            if entity.description.is_empty() {
+                problems.push(format!("`{path}.description`: must not be empty"));
+            }
+
+            for (name, on_function) in &entity.on_functions {
+                let path = format!("{path}.on_functions{}", json_key(name));
+
+                if !name.starts_with("on_") {
+                    problems.push(format!("`{path}`: on_function names must start with `on_`"));
+                } else if !is_identifier(name) {
+                    problems.push(format!("`{path}`: `{name}` is not a valid grug name"));
+                }
+
+                on_function.validate(&path, false, self, &mut problems);
+            }
+        }
+
+        for (name, game_function) in &self.game_functions {
+            let path = format!("game_functions{}", json_key(name));
+
+            if name.starts_with("on_") {
+                problems.push(format!(
+                    "`{path}`: game function names must not start with `on_`"
+                ));
+            } else if !is_identifier(name) || RESERVED_WORDS.contains(&name.as_str()) {
+                problems.push(format!("`{path}`: `{name}` is not a valid grug name"));
+            }
+
+            game_function.validate(&path, true, self, &mut problems);
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// Every change from `old` to `new`, each one classified as breaking or not
+    ///
+    /// A change is breaking if mods made for `old` can fail to compile against `new`,
+    /// like removing something or changing the type of an argument.
+    ///
+    /// # Example
+    /// ```
+    /// use grug_rs::mod_api_type::{GameFunction, ModAPI};
+    ///
+    /// let old = ModAPI::builder()
+    ///     .entity("World", "The world")
+    ///     .on_function("on_update", GameFunction::new("Called every tick"))
+    ///     .game_function("print", GameFunction::new("Prints").argument("msg", "string"))
+    ///     .build();
+    /// let new = ModAPI::builder()
+    ///     .entity("World", "The whole world")
+    ///     .on_function("on_update", GameFunction::new("Called every tick"))
+    ///     .game_function("print", GameFunction::new("Prints").argument("msg", "i32"))
+    ///     .build();
+    ///
+    /// let changes = ModAPI::diff(&old, &new);
+    /// assert_eq!(changes[0].to_string(), "`entities.World.description` changed");
+    /// assert!(!changes[0].breaking);
+    /// assert_eq!(
+    ///     changes[1].to_string(),
+    ///     "`game_functions.print.arguments` changed from `(msg: string)` to `(msg: i32)`"
+    /// );
+    /// assert!(changes[1].breaking);
+    /// ```
+    pub fn diff(old: &ModAPI, new: &ModAPI) -> Vec<Change> {
+        mod_api_diff::diff(old, new)
+    }
+}
+
+/// Reads and deserializes a mod API or fragment in the format of its extension, see `ModAPI::load`
+fn parse_file<T: DeserializeOwned>(path: &Path) -> Result<T, GrugError> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default();
+
+    let parse: fn(&str) -> Result<T, ParseError> = match extension {
+        "json" => from_json,
+        #[cfg(feature = "toml")]
+        "toml" => from_toml,
+        #[cfg(feature = "yaml")]
+        "yaml" | "yml" => from_yaml,
+        #[cfg(not(feature = "toml"))]
+        "toml" => {
+            return Err(GrugError::ModAPIFeature {
+                path: path.to_path_buf(),
+                feature: "toml",
+            });
+        }
+        #[cfg(not(feature = "yaml"))]
+        "yaml" | "yml" => {
+            return Err(GrugError::ModAPIFeature {
+                path: path.to_path_buf(),
+                feature: "yaml",
+            });
+        }
+        _ => {
+            return Err(GrugError::ModAPIFormat {
+                path: path.to_path_buf(),
+            });
+        }
+    };
+
+    let text = read_to_string(path).map_err(|x| GrugError::ReadModAPI {
+        path: path.to_path_buf(),
+        error: x.to_string(),
+    })?;
+
+    let parsed = parse(&text);
+
+    parsed.map_err(|(line, column, error)| GrugError::Deserialize {
+        path: path.to_path_buf(),
+        line,
+        column,
+        error,
+    })
+}
+
+/// A parse error with its line, column and message
+type ParseError = (usize, usize, String);
+
+fn from_json<T: DeserializeOwned>(text: &str) -> Result<T, ParseError> {
+    from_str(text).map_err(|x| {
+        let (line, column) = (x.line(), x.column());
+        (line, column, without_location(x.to_string(), line, column))
+    })
+}
+
+#[cfg(feature = "toml")]
+fn from_toml<T: DeserializeOwned>(text: &str) -> Result<T, ParseError> {
+    toml::from_str(text).map_err(|x| {
+        let (line, column) = x.span().map_or((0, 0), |span| {
+            let before = &text[..span.start];
+            let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+            (
+                before.matches('\n').count() + 1,
+                before[line_start..].chars().count() + 1,
+            )
+        });
+        (line, column, x.message().to_string())
+    })
+}
+
+#[cfg(feature = "yaml")]
+fn from_yaml<T: DeserializeOwned>(text: &str) -> Result<T, ParseError> {
+    serde_yaml::from_str(text).map_err(|x| {
+        let (line, column) = x
+            .location()
+            .map_or((0, 0), |location| (location.line(), location.column()));
+        (line, column, without_location(x.to_string(), line, column))
+    })
+}
+
+/// Removes the location serde_json and serde_yaml put in their messages
+pub(crate) fn without_location(message: String, line: usize, column: usize) -> String {
+    message.replace(&format!(" at line {line} column {column}"), "")
+}
+
+/// Whether the file is a mod API, based on its extension
+///
+/// TOML and YAML files count even without their feature, so `load` can say it's missing.
+fn is_mod_api_file(path: &Path) -> bool {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default();
+
+    matches!(extension, "json" | "toml" | "yaml" | "yml")
+}
+
+/// The files directly inside of `dir` that `load` can read
+fn fragment_paths(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut paths = vec![];
+
+    for entry in read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_file() && is_mod_api_file(&path) {
+            paths.push(path);
+        }
+    }
+
+    Ok(paths)
+}
+
+/// Words that grug doesn't allow as names
+const RESERVED_WORDS: &[&str] = &[
+    "and", "or", "not", "true", "false", "if", "else", "while", "break", "return", "continue", "me",
+];
+
+/// `.name` for names that are identifiers, otherwise `["name"]`
+pub(crate) fn json_key(name: &str) -> String {
+    if is_identifier(name) {
+        format!(".{name}")
+    } else {
+        format!("[{name:?}]")
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_pascal_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(deny_unknown_fields))]
 pub struct Entity {
+    #[cfg_attr(feature = "schemars", schemars(length(min = 1)))]
     pub description: String,
+    /// The functions mods can define for this entity, by name
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "on_functions_schema"))]
     pub on_functions: LinkedHashMap<String, GameFunction>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// Like `game_functions`, except that on_functions can't return anything
+#[cfg(feature = "schemars")]
+fn on_functions_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+    let game_function = generator.subschema_for::<GameFunction>();
+
+    schemars::json_schema!({
+        "type": "object",
+        "additionalProperties": {
+            "allOf": [game_function, { "not": { "required": ["return_type"] } }]
+        }
+    })
+}
+
+impl Entity {
+    pub fn new<S: ToString>(description: S) -> Self {
+        Self {
+            description: description.to_string(),
+            on_functions: LinkedHashMap::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(deny_unknown_fields))]
 pub struct GameFunction {
+    #[cfg_attr(feature = "schemars", schemars(length(min = 1)))]
     pub description: String,
-    #[serde(default)]
+    // grug expects `return_type` before `arguments`, and no empty `arguments`.
+    // It also doesn't accept `null`, so it's left out of the schema instead
+    /// Left out when it returns nothing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schemars", schemars(with = "GrugType"))]
+    pub return_type: Option<GrugType>,
+    /// Left out when there are none
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "schemars", schemars(length(min = 1)))]
     pub arguments: Vec<Argument>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl GameFunction {
+    pub fn new<S: ToString>(description: S) -> Self {
+        Self {
+            description: description.to_string(),
+            return_type: None,
+            arguments: vec![],
+        }
+    }
+
+    /// Adds an argument, like `.argument("msg", "string")`
+    /// or `.argument("sprite", GrugType::Resource { extension: ".png".to_string() })`
+    pub fn argument<S: ToString, T: Into<GrugType>>(mut self, name: S, type_: T) -> Self {
+        self.arguments.push(Argument {
+            name: name.to_string(),
+            type_: type_.into(),
+        });
+        self
+    }
+
+    /// Only game functions can return something
+    pub fn return_type<T: Into<GrugType>>(mut self, type_: T) -> Self {
+        self.return_type = Some(type_.into());
+        self
+    }
+
+    fn validate(
+        &self,
+        path: &str,
+        is_game_function: bool,
+        mod_api: &ModAPI,
+        problems: &mut Vec<String>,
+    ) {
+        if self.description.is_empty() {
+            problems.push(format!("`{path}.description`: must not be empty"));
+        }
+
+        if let Some(return_type) = &self.return_type {
+            let path = format!("{path}.return_type");
+
+            if !is_game_function {
+                problems.push(format!("`{path}`: on_functions can't return anything"));
+            } else if matches!(
+                return_type,
+                GrugType::Resource { .. } | GrugType::Entity { .. }
+            ) {
+                problems.push(format!(
+                    "`{path}`: game functions can't return `{return_type}`"
+                ));
+            } else {
+                return_type.validate(&path, &path, mod_api, problems);
+            }
+        }
+
+        for (i, argument) in self.arguments.iter().enumerate() {
+            let path = format!("{path}.arguments[{i}]");
+
+            if !is_identifier(&argument.name) || RESERVED_WORDS.contains(&argument.name.as_str()) {
+                problems.push(format!(
+                    "`{path}.name`: `{}` is not a valid grug name",
+                    argument.name
+                ));
+            } else if self.arguments[..i]
+                .iter()
+                .any(|other| other.name == argument.name)
+            {
+                problems.push(format!(
+                    "`{path}.name`: there already is an argument named `{}`",
+                    argument.name
+                ));
+            }
+
+            if !is_game_function
+                && matches!(
+                    argument.type_,
+                    GrugType::Resource { .. } | GrugType::Entity { .. }
+                )
+            {
+                problems.push(format!(
+                    "`{path}.type`: on_function arguments can't be `{}`",
+                    argument.type_
+                ));
+            } else {
+                argument.type_.validate(
+                    &format!("{path}.type"),
+                    &format!("{path}.entity_type"),
+                    mod_api,
+                    problems,
+                );
+            }
+        }
+    }
+}
+
+/// A type in the mod API
+///
+/// Serialized as its name, like `"string"`, with `resource_extension` and `entity_type`
+/// being separate fields of an `Argument`.
+///
+/// # Example
+/// ```
+/// use grug_rs::mod_api_type::{Argument, GrugType};
+///
+/// let argument: Argument = serde_json::from_str(
+///     r#"{ "name": "sprite", "type": "resource", "resource_extension": ".png" }"#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(
+///     argument.type_,
+///     GrugType::Resource {
+///         extension: ".png".to_string()
+///     }
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GrugType {
+    Bool,
+    I32,
+    F32,
+    String,
+    Id,
+    /// A path to a file inside of the mod, like `"sprites/goblin.png"`
+    Resource {
+        /// Like `".png"`, an empty extension allows every file
+        extension: String,
+    },
+    /// An entity, like `"base_game:goblin"`
+    Entity {
+        /// Like `"Enemy"`, an empty entity type allows every entity
+        entity_type: String,
+    },
+    /// A type of your game, which grug passes around as an id
+    Custom(String),
+}
+
+impl GrugType {
+    /// The name used in the mod API, like `"string"`
+    pub fn name(&self) -> &str {
+        match self {
+            GrugType::Bool => "bool",
+            GrugType::I32 => "i32",
+            GrugType::F32 => "f32",
+            GrugType::String => "string",
+            GrugType::Id => "id",
+            GrugType::Resource { .. } => "resource",
+            GrugType::Entity { .. } => "entity",
+            GrugType::Custom(name) => name,
+        }
+    }
+
+    fn validate(
+        &self,
+        type_path: &str,
+        entity_type_path: &str,
+        mod_api: &ModAPI,
+        problems: &mut Vec<String>,
+    ) {
+        match self {
+            GrugType::Custom(name) if !is_pascal_case(name) => problems.push(format!(
+                "`{type_path}`: unknown type `{name}`, custom types must be in PascalCase"
+            )),
+            GrugType::Entity { entity_type }
+                if !entity_type.is_empty() && !mod_api.entities.contains_key(entity_type) =>
+            {
+                problems.push(format!(
+                    "`{entity_type_path}`: `{entity_type}` is not an entity"
+                ))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Parses the name of a type, resources and entities allow everything
+impl From<&str> for GrugType {
+    fn from(name: &str) -> Self {
+        match name {
+            "bool" => GrugType::Bool,
+            "i32" => GrugType::I32,
+            "f32" => GrugType::F32,
+            "string" => GrugType::String,
+            "id" => GrugType::Id,
+            "resource" => GrugType::Resource {
+                extension: String::new(),
+            },
+            "entity" => GrugType::Entity {
+                entity_type: String::new(),
+            },
+            custom => GrugType::Custom(custom.to_string()),
+        }
+    }
+}
+
+impl From<String> for GrugType {
+    fn from(name: String) -> Self {
+        GrugType::from(name.as_str())
+    }
+}
+
+impl std::fmt::Display for GrugType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl Serialize for GrugType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for GrugType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(GrugType::from)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for GrugType {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "GrugType".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "description": "`bool`, `i32`, `f32`, `string`, `id`, `resource`, `entity` or a custom type"
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(into = "RawArgument", try_from = "RawArgument")]
 pub struct Argument {
     pub name: String,
-    #[serde(rename(serialize = "type", deserialize = "type"))]
-    pub type_: String,
+    pub type_: GrugType,
+}
+
+/// Like `sprite: resource(".png")`
+impl std::fmt::Display for Argument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.type_ {
+            GrugType::Resource { extension } => {
+                write!(f, "{}: {}({extension:?})", self.name, self.type_)
+            }
+            GrugType::Entity { entity_type } => {
+                write!(f, "{}: {}({entity_type:?})", self.name, self.type_)
+            }
+            type_ => write!(f, "{}: {type_}", self.name),
+        }
+    }
+}
+
+/// How an `Argument` looks in the mod API
+///
+/// The schema of `Argument` is this one, with the checks of `TryFrom` added.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "schemars",
+    schemars(
+        description = "An argument of an on_function or game function",
+        deny_unknown_fields,
+        extend("allOf" = [
+            {
+                "if": { "properties": { "type": { "const": "resource" } } },
+                "then": { "required": ["resource_extension"] },
+                "else": { "not": { "required": ["resource_extension"] } }
+            },
+            {
+                "if": { "properties": { "type": { "const": "entity" } } },
+                "then": { "required": ["entity_type"] },
+                "else": { "not": { "required": ["entity_type"] } }
+            }
+        ])
+    )
+)]
+struct RawArgument {
+    name: String,
+    /// `bool`, `i32`, `f32`, `string`, `id`, `resource`, `entity` or a custom type
+    #[serde(rename = "type")]
+    type_: String,
+    /// Needed by `resource` arguments, like `.png`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    resource_extension: Option<String>,
+    /// Needed by `entity` arguments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    entity_type: Option<String>,
+}
+
+impl TryFrom<RawArgument> for Argument {
+    type Error = String;
+
+    fn try_from(raw: RawArgument) -> Result<Self, Self::Error> {
+        let type_ = match (raw.type_.as_str(), raw.resource_extension, raw.entity_type) {
+            ("resource", Some(extension), None) => GrugType::Resource { extension },
+            ("resource", _, _) => {
+                return Err("`resource` arguments need just a `resource_extension`".to_string());
+            }
+            ("entity", None, Some(entity_type)) => GrugType::Entity { entity_type },
+            ("entity", _, _) => {
+                return Err("`entity` arguments need just an `entity_type`".to_string());
+            }
+            (name, None, None) => GrugType::from(name),
+            (name, _, _) => {
+                return Err(format!(
+                    "`{name}` arguments can't have a `resource_extension` or `entity_type`"
+                ));
+            }
+        };
+
+        Ok(Self {
+            name: raw.name,
+            type_,
+        })
+    }
+}
+
+impl From<Argument> for RawArgument {
+    fn from(argument: Argument) -> Self {
+        let name = argument.type_.name().to_string();
+
+        let (resource_extension, entity_type) = match argument.type_ {
+            GrugType::Resource { extension } => (Some(extension), None),
+            GrugType::Entity { entity_type } => (None, Some(entity_type)),
+            _ => (None, None),
+        };
+
+        Self {
+            name: argument.name,
+            type_: name,
+            resource_extension,
+            entity_type,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mod_api(json: &str) -> ModAPI {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn validate_accepts_a_valid_mod_api() {
+        let mod_api = mod_api(
+            r#"{
+                "entities": {
+                    "Enemy": {
+                        "description": "Fights the player",
+                        "on_functions": {
+                            "on_hit": {
+                                "description": "Called when hit",
+                                "arguments": [{ "name": "damage", "type": "i32" }]
+                            }
+                        }
+                    }
+                },
+                "game_functions": {
+                    "spawn": {
+                        "description": "Spawns an entity",
+                        "return_type": "id",
+                        "arguments": [
+                            { "name": "entity", "type": "entity", "entity_type": "Enemy" },
+                            { "name": "sprite", "type": "resource", "resource_extension": ".png" },
+                            { "name": "target", "type": "Target" }
+                        ]
+                    }
+                }
+            }"#,
+        );
+
+        assert_eq!(mod_api.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_names() {
+        let mod_api = mod_api(
+            r#"{
+                "entities": {
+                    "enemy": {
+                        "description": "",
+                        "on_functions": {
+                            "hit": { "description": "Called when hit" },
+                            "on_big-hit": { "description": "Called when hit hard" }
+                        }
+                    }
+                },
+                "game_functions": {
+                    "on_spawn": { "description": "Spawns" },
+                    "while": { "description": "Loops" },
+                    "print": {
+                        "description": "Prints",
+                        "arguments": [
+                            { "name": "me", "type": "string" },
+                            { "name": "msg", "type": "string" },
+                            { "name": "msg", "type": "string" }
+                        ]
+                    }
+                }
+            }"#,
+        );
+
+        assert_eq!(
+            mod_api.validate().unwrap_err(),
+            [
+                "`entities.enemy`: entity names must be in PascalCase",
+                "`entities.enemy.description`: must not be empty",
+                "`entities.enemy.on_functions.hit`: on_function names must start with `on_`",
+                r#"`entities.enemy.on_functions["on_big-hit"]`: `on_big-hit` is not a valid grug name"#,
+                "`game_functions.on_spawn`: game function names must not start with `on_`",
+                "`game_functions.while`: `while` is not a valid grug name",
+                "`game_functions.print.arguments[0].name`: `me` is not a valid grug name",
+                "`game_functions.print.arguments[2].name`: there already is an argument named `msg`",
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_types() {
+        let mod_api = mod_api(
+            r#"{
+                "entities": {
+                    "World": {
+                        "description": "The world",
+                        "on_functions": {
+                            "on_update": {
+                                "description": "Called every tick",
+                                "return_type": "i32",
+                                "arguments": [
+                                    { "name": "sprite", "type": "resource", "resource_extension": ".png" }
+                                ]
+                            }
+                        }
+                    }
+                },
+                "game_functions": {
+                    "find": {
+                        "description": "Finds an entity",
+                        "return_type": "entity",
+                        "arguments": [
+                            { "name": "kind", "type": "entity", "entity_type": "Enemy" },
+                            { "name": "target", "type": "target" }
+                        ]
+                    }
+                }
+            }"#,
+        );
+
+        assert_eq!(
+            mod_api.validate().unwrap_err(),
+            [
+                "`entities.World.on_functions.on_update.return_type`: on_functions can't return anything",
+                "`entities.World.on_functions.on_update.arguments[0].type`: on_function arguments can't be `resource`",
+                "`game_functions.find.return_type`: game functions can't return `entity`",
+                "`game_functions.find.arguments[0].entity_type`: `Enemy` is not an entity",
+                "`game_functions.find.arguments[1].type`: unknown type `target`, custom types must be in PascalCase",
+            ]
+        );
+    }
+
+    #[test]
+    fn entities_of_any_type() {
+        let mod_api = mod_api(
+            r#"{
+                "entities": {},
+                "game_functions": {
+                    "despawn": {
+                        "description": "Despawns an entity",
+                        "arguments": [{ "name": "entity", "type": "entity", "entity_type": "" }]
+                    }
+                }
+            }"#,
+        );
+
+        assert_eq!(mod_api.validate(), Ok(()));
+    }
+
+    #[test]
+    fn load_rejects_unknown_extensions() {
+        for path in ["mod_api.txt", "mod_api"] {
+            assert!(matches!(
+                ModAPI::load(path),
+                Err(GrugError::ModAPIFormat { path: error_path }) if error_path == Path::new(path)
+            ));
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "toml"))]
+    fn load_toml_without_the_feature() {
+        let error = ModAPI::load("mod_api.toml").unwrap_err();
+
+        assert!(matches!(
+            error,
+            GrugError::ModAPIFeature {
+                feature: "toml",
+                ..
+            }
+        ));
+        assert_eq!(
+            error.to_string(),
+            "Enable the `toml` feature of grug-rs to load `mod_api.toml`"
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "yaml"))]
+    fn load_yaml_without_the_feature() {
+        assert!(matches!(
+            ModAPI::load("mod_api.yml"),
+            Err(GrugError::ModAPIFeature {
+                feature: "yaml",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn json_errors_have_their_position() {
+        let (line, column, error) = from_json::<ModAPI>("{\n  \"entities\": 1\n}").unwrap_err();
+
+        assert_eq!((line, column), (2, 15));
+        assert!(!error.contains("line"));
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn toml_errors_have_their_position() {
+        let toml = r#"entities = {}
+
+[game_functions.print]
+description = "Prints"
+arguments = [{ name = "msg" }]
+"#;
+        let (line, column, error) = from_toml::<ModAPI>(toml).unwrap_err();
+
+        // The argument that's missing its `type`
+        assert_eq!((line, column), (5, 14));
+        assert!(error.contains("missing field `type`"), "{error}");
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn yaml_errors_have_their_position() {
+        let yaml = "entities: {}
+game_functions:
+  print:
+    description: Prints
+    arguments:
+      - name: msg
+";
+        let (line, column, error) = from_yaml::<ModAPI>(yaml).unwrap_err();
+
+        // The argument that's missing its `type`
+        assert_eq!((line, column), (6, 9));
+        assert!(error.contains("missing field `type`"), "{error}");
+        assert!(!error.contains("line"), "{error}");
+    }
+
+    #[test]
+    fn unknown_positions_are_left_out() {
+        let error = |line, column| {
+            GrugError::Deserialize {
+                path: PathBuf::from("mod_api.yaml"),
+                line,
+                column,
+                error: "invalid type".to_string(),
+            }
+            .to_string()
+        };
+
+        assert_eq!(
+            error(3, 7),
+            "Failed to deserialize `mod_api.yaml` at line 3, column 7: `invalid type`"
+        );
+        assert_eq!(
+            error(0, 0),
+            "Failed to deserialize `mod_api.yaml`: `invalid type`"
+        );
+    }
+
+    #[test]
+    fn whole_mod_apis_need_entities_and_game_functions() {
+        let (_, _, error) = from_json::<ModAPI>(r#"{ "entities": {} }"#).unwrap_err();
+        assert_eq!(error, "missing field `game_functions`");
+
+        assert!(from_json::<Fragment>(r#"{ "entities": {} }"#).is_ok());
+    }
+
+    /// A new directory with `files` in it, named after the test
+    fn fragments_dir(test: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("grug-rs-{test}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for (name, text) in files {
+            write(dir.join(name), text).unwrap();
+        }
+
+        dir
+    }
+
+    #[test]
+    fn load_dir_merges_in_file_name_order() {
+        let mod_api = ModAPI::load_dir("examples/mod_api_fragments/mod_api").unwrap();
+
+        // `enemies.json`, `game_functions.json`, then `world.json`
+        assert_eq!(
+            mod_api.entities.keys().collect::<Vec<_>>(),
+            ["Enemy", "World"]
+        );
+        assert_eq!(
+            mod_api.game_functions.keys().collect::<Vec<_>>(),
+            ["println"]
+        );
+        assert_eq!(mod_api.validate(), Ok(()));
+    }
+
+    #[test]
+    fn load_dir_names_both_conflicting_fragments() {
+        let print = r#"{ "game_functions": { "print": { "description": "Prints" } } }"#;
+        let dir = fragments_dir(
+            "load_dir_conflicts",
+            &[
+                ("b.json", print),
+                ("a.json", print),
+                ("notes.txt", "ignored"),
+            ],
+        );
+
+        let error = ModAPI::load_dir(&dir).unwrap_err();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let GrugError::ModAPIConflicts { conflicts } = error else {
+            panic!("expected conflicts, got {error}");
+        };
+        assert_eq!(
+            conflicts,
+            [format!(
+                "`game_functions.print` is defined by both `{}` and `{}`",
+                dir.join("a.json").display(),
+                dir.join("b.json").display()
+            )]
+        );
+    }
+
+    #[test]
+    fn json_keys() {
+        assert_eq!(json_key("World"), ".World");
+        assert_eq!(json_key("on_big-hit"), r#"["on_big-hit"]"#);
+        assert_eq!(json_key("1st"), r#"["1st"]"#);
+    }
 }
@@ -0,0 +1,183 @@
+use std::path::PathBuf;
+
+use linked_hash_map::LinkedHashMap;
+
+use crate::GrugFile;
+
+/// What to do when several mods define the same entity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// Run every definition
+    #[default]
+    RunAll,
+    /// Only run the definition of the mod that is loaded last
+    LastWins,
+    /// Fail to regenerate mods with `GrugError::Conflicts`
+    Reject,
+}
+
+/// An entity that is defined by more than one mod
+///
+/// grug itself already rejects an entity that is defined twice by the same mod.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    /// The entity without its mod, like `goblin`
+    pub entity_name: String,
+    pub entity_type: String,
+    /// Every definition in load order, each one overriding the ones before it
+    pub definitions: Vec<Definition>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Definition {
+    /// The entity including its mod, like `base_game:goblin`
+    pub entity: String,
+    /// The path relative to the mods folder
+    pub relative_path: PathBuf,
+}
+
+impl Conflict {
+    /// The mods defining the entity, in load order
+    pub fn mods(&self) -> Vec<&str> {
+        self.definitions
+            .iter()
+            .map(|definition| {
+                definition
+                    .entity
+                    .split_once(':')
+                    .map_or("", |(mod_name, _)| mod_name)
+            })
+            .collect()
+    }
+
+    /// The definition that is run under `ConflictPolicy::LastWins`
+    pub fn winner(&self) -> &Definition {
+        self.definitions.last().unwrap()
+    }
+}
+
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "`{}` ({}) is defined by {}",
+            self.entity_name,
+            self.entity_type,
+            self.mods().join(" -> ")
+        )
+    }
+}
+
+/// Finds every entity that is defined by more than one of `files`
+///
+/// `files` should be in load order, see `Grug::files`.
+pub fn find_conflicts(files: &[GrugFile]) -> Vec<Conflict> {
+    let mut definitions: LinkedHashMap<(&str, &str), Vec<Definition>> = LinkedHashMap::new();
+
+    for file in files {
+        definitions
+            .entry((file.entity_name(), file.entity_type()))
+            .or_default()
+            .push(Definition {
+                entity: file.entity().to_string(),
+                relative_path: file.relative_path().to_path_buf(),
+            });
+    }
+
+    definitions
+        .into_iter()
+        .filter(|(_, definitions)| definitions.len() > 1)
+        .map(|((entity_name, entity_type), definitions)| Conflict {
+            entity_name: entity_name.to_string(),
+            entity_type: entity_type.to_string(),
+            definitions,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ffi::CStr, path::Path};
+
+    use grug_sys::grug_file;
+
+    use super::*;
+    use crate::mod_api_type::ModAPI;
+
+    fn file(
+        mod_name: &str,
+        name: &'static CStr,
+        entity: &'static CStr,
+        entity_type: &'static CStr,
+    ) -> GrugFile {
+        let mut file: grug_file = unsafe { std::mem::zeroed() };
+        file.name = name.as_ptr();
+        file.entity = entity.as_ptr();
+        file.entity_type = entity_type.as_ptr();
+
+        unsafe { GrugFile::in_dir(file, Path::new(mod_name), &ModAPI::default()) }
+    }
+
+    #[test]
+    fn no_conflicts() {
+        let files = [
+            file("base", c"goblin-Enemy.grug", c"base:goblin", c"Enemy"),
+            file("base", c"slime-Enemy.grug", c"base:slime", c"Enemy"),
+        ];
+
+        assert!(find_conflicts(&files).is_empty());
+    }
+
+    #[test]
+    fn conflicts_in_load_order() {
+        let files = [
+            file("base", c"goblin-Enemy.grug", c"base:goblin", c"Enemy"),
+            file("base", c"slime-Enemy.grug", c"base:slime", c"Enemy"),
+            file("hard", c"goblin-Enemy.grug", c"hard:goblin", c"Enemy"),
+            file("extra", c"goblin-Enemy.grug", c"extra:goblin", c"Enemy"),
+        ];
+
+        let conflicts = find_conflicts(&files);
+
+        assert_eq!(conflicts.len(), 1);
+        let conflict = &conflicts[0];
+        assert_eq!(conflict.entity_name, "goblin");
+        assert_eq!(conflict.entity_type, "Enemy");
+        assert_eq!(conflict.mods(), ["base", "hard", "extra"]);
+        assert_eq!(conflict.winner().entity, "extra:goblin");
+        assert_eq!(
+            conflict.winner().relative_path,
+            Path::new("extra/goblin-Enemy.grug")
+        );
+        assert_eq!(
+            conflict.to_string(),
+            "`goblin` (Enemy) is defined by base -> hard -> extra"
+        );
+    }
+
+    #[test]
+    fn same_name_with_another_entity_type_is_no_conflict() {
+        let files = [
+            file("base", c"goblin-Enemy.grug", c"base:goblin", c"Enemy"),
+            file("extra", c"goblin-Pet.grug", c"extra:goblin", c"Pet"),
+        ];
+
+        assert!(find_conflicts(&files).is_empty());
+    }
+
+    #[test]
+    fn conflicts_are_ordered_by_first_definition() {
+        let files = [
+            file("base", c"slime-Enemy.grug", c"base:slime", c"Enemy"),
+            file("base", c"goblin-Enemy.grug", c"base:goblin", c"Enemy"),
+            file("extra", c"goblin-Enemy.grug", c"extra:goblin", c"Enemy"),
+            file("extra", c"slime-Enemy.grug", c"extra:slime", c"Enemy"),
+        ];
+
+        let names: Vec<String> = find_conflicts(&files)
+            .into_iter()
+            .map(|conflict| conflict.entity_name)
+            .collect();
+        assert_eq!(names, ["slime", "goblin"]);
+    }
+}
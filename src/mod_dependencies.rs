@@ -0,0 +1,221 @@
+use semver::{Version, VersionReq};
+
+use crate::{GrugError, LoadedMod};
+
+/// Orders mods so that every mod comes after its dependencies
+///
+/// Mods that don't depend on each other keep the order they're given in.
+/// Every dependency has to be in `mods` and match its version requirement.
+pub fn resolve_load_order<'a>(mods: &[&'a LoadedMod]) -> Result<Vec<&'a LoadedMod>, GrugError> {
+    for mod_ in mods {
+        for (dependency, requirement) in &mod_.info.dependencies {
+            let Some(found) = mods.iter().find(|other| &other.dir_name == dependency) else {
+                return Err(GrugError::MissingDependency {
+                    name: mod_.dir_name.clone(),
+                    dependency: dependency.clone(),
+                    requirement: requirement.clone(),
+                });
+            };
+
+            let matches = match (
+                VersionReq::parse(requirement),
+                Version::parse(&found.info.version),
+            ) {
+                (Ok(requirement), Ok(version)) => requirement.matches(&version),
+                _ => false,
+            };
+
+            if !matches {
+                return Err(GrugError::DependencyVersion {
+                    name: mod_.dir_name.clone(),
+                    dependency: dependency.clone(),
+                    requirement: requirement.clone(),
+                    version: found.info.version.clone(),
+                });
+            }
+        }
+    }
+
+    let mut remaining: Vec<&LoadedMod> = mods.to_vec();
+    let mut ordered: Vec<&LoadedMod> = Vec::with_capacity(mods.len());
+
+    while !remaining.is_empty() {
+        // Take the first mod whose dependencies have all been loaded
+        let next = remaining.iter().position(|mod_| {
+            mod_.info
+                .dependencies
+                .keys()
+                .all(|dependency| ordered.iter().any(|other| &other.dir_name == dependency))
+        });
+
+        match next {
+            Some(index) => ordered.push(remaining.remove(index)),
+            None => {
+                return Err(GrugError::DependencyCycle {
+                    mods: find_cycle(&remaining),
+                });
+            }
+        }
+    }
+
+    Ok(ordered)
+}
+
+/// Follows dependencies from the first mod until one repeats
+///
+/// Every mod in `remaining` has a dependency in `remaining`, so this always finds a cycle.
+fn find_cycle(remaining: &[&LoadedMod]) -> Vec<String> {
+    let mut path: Vec<&LoadedMod> = vec![remaining[0]];
+
+    loop {
+        let current = path.last().unwrap();
+
+        let next = current
+            .info
+            .dependencies
+            .keys()
+            .find_map(|dependency| remaining.iter().find(|other| &other.dir_name == dependency))
+            .unwrap();
+
+        if let Some(start) = path.iter().position(|mod_| mod_.dir_name == next.dir_name) {
+            let mut cycle: Vec<String> = path[start..]
+                .iter()
+                .map(|mod_| mod_.dir_name.clone())
+                .collect();
+            cycle.push(next.dir_name.clone());
+            return cycle;
+        }
+
+        path.push(next);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use linked_hash_map::LinkedHashMap;
+
+    use super::*;
+    use crate::mod_info::{Compatibility, ModInfo};
+
+    fn mod_(name: &str, version: &str, dependencies: &[(&str, &str)]) -> LoadedMod {
+        LoadedMod {
+            dir_name: name.to_string(),
+            path: PathBuf::from(name),
+            info: ModInfo {
+                name: name.to_string(),
+                version: version.to_string(),
+                game_version: "1.0.0".to_string(),
+                author: "LambdaLemon".to_string(),
+                dependencies: dependencies
+                    .iter()
+                    .map(|(name, requirement)| (name.to_string(), requirement.to_string()))
+                    .collect::<LinkedHashMap<_, _>>(),
+            },
+            compatibility: Compatibility::Unchecked,
+        }
+    }
+
+    fn order(mods: &[LoadedMod]) -> Result<Vec<String>, GrugError> {
+        let mods: Vec<&LoadedMod> = mods.iter().collect();
+
+        resolve_load_order(&mods)
+            .map(|ordered| ordered.iter().map(|mod_| mod_.dir_name.clone()).collect())
+    }
+
+    #[test]
+    fn independent_mods_keep_their_order() {
+        let mods = [
+            mod_("b", "1.0.0", &[]),
+            mod_("a", "1.0.0", &[]),
+            mod_("c", "1.0.0", &[]),
+        ];
+
+        assert_eq!(order(&mods).unwrap(), ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn dependencies_go_first() {
+        let mods = [
+            mod_("app", "1.0.0", &[("lib", "^1.2")]),
+            mod_("other", "1.0.0", &[]),
+            mod_("lib", "1.3.0", &[("core", "*")]),
+            mod_("core", "0.1.0", &[]),
+        ];
+
+        assert_eq!(order(&mods).unwrap(), ["other", "core", "lib", "app"]);
+    }
+
+    #[test]
+    fn missing_dependency() {
+        let mods = [mod_("app", "1.0.0", &[("lib", "^1")])];
+
+        assert!(matches!(
+            order(&mods),
+            Err(GrugError::MissingDependency { name, dependency, .. })
+                if name == "app" && dependency == "lib"
+        ));
+    }
+
+    #[test]
+    fn dependency_with_the_wrong_version() {
+        let mods = [
+            mod_("app", "1.0.0", &[("lib", "^2")]),
+            mod_("lib", "1.3.0", &[]),
+        ];
+
+        assert!(matches!(
+            order(&mods),
+            Err(GrugError::DependencyVersion { version, .. }) if version == "1.3.0"
+        ));
+    }
+
+    #[test]
+    fn dependency_with_an_invalid_version() {
+        let mods = [
+            mod_("app", "1.0.0", &[("lib", "^1")]),
+            mod_("lib", "one", &[]),
+        ];
+
+        assert!(matches!(
+            order(&mods),
+            Err(GrugError::DependencyVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn cycle() {
+        let mods = [
+            mod_("free", "1.0.0", &[]),
+            mod_("a", "1.0.0", &[("b", "*")]),
+            mod_("b", "1.0.0", &[("c", "*")]),
+            mod_("c", "1.0.0", &[("a", "*")]),
+        ];
+
+        assert!(matches!(
+            order(&mods),
+            Err(GrugError::DependencyCycle { mods }) if mods == ["a", "b", "c", "a"]
+        ));
+    }
+
+    #[test]
+    fn find_cycle_skips_mods_that_only_lead_into_it() {
+        let mods = [
+            mod_("start", "1.0.0", &[("a", "*")]),
+            mod_("a", "1.0.0", &[("b", "*")]),
+            mod_("b", "1.0.0", &[("a", "*")]),
+        ];
+        let remaining: Vec<&LoadedMod> = mods.iter().collect();
+
+        assert_eq!(find_cycle(&remaining), ["a", "b", "a"]);
+    }
+
+    #[test]
+    fn self_dependency_is_a_cycle() {
+        let mods = [mod_("a", "1.0.0", &[("a", "*")])];
+        let remaining: Vec<&LoadedMod> = mods.iter().collect();
+
+        assert_eq!(find_cycle(&remaining), ["a", "a"]);
+    }
+}
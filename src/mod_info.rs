@@ -0,0 +1,500 @@
+#[cfg(feature = "runtime")]
+use std::fs::read_dir;
+use std::{
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use linked_hash_map::LinkedHashMap;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, from_str};
+
+use crate::GrugError;
+
+/// The contents of a mod's `about.json`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(deny_unknown_fields))]
+pub struct ModInfo {
+    #[cfg_attr(feature = "schemars", schemars(length(min = 1)))]
+    pub name: String,
+    /// A semver version, like `1.2.0`
+    #[cfg_attr(feature = "schemars", schemars(regex(pattern = VERSION_PATTERN)))]
+    pub version: String,
+    #[cfg_attr(feature = "schemars", schemars(length(min = 1)))]
+    pub game_version: String,
+    #[cfg_attr(feature = "schemars", schemars(length(min = 1)))]
+    pub author: String,
+    /// The directory names of the mods this mod needs, with semver version requirements
+    ///
+    /// ```json
+    /// "dependencies": {
+    ///     "shared_lib": "^1.2"
+    /// }
+    /// ```
+    #[serde(default, skip_serializing_if = "LinkedHashMap::is_empty")]
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "dependencies_schema"))]
+    pub dependencies: LinkedHashMap<String, String>,
+}
+
+/// Matches the versions `Version::parse` accepts, from <https://semver.org>
+#[cfg(feature = "schemars")]
+const VERSION_PATTERN: &str = r"^(0|[1-9]\d*)\.(0|[1-9]\d*)\.(0|[1-9]\d*)(?:-((?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*)(?:\.(?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*))*))?(?:\+([0-9a-zA-Z-]+(?:\.[0-9a-zA-Z-]+)*))?$";
+
+/// Matches the semver version requirements `VersionReq::parse` accepts, like `^1.2` or `>=1.2, <1.5`
+///
+/// JSON Schema can't run `VersionReq::parse`, so this is close to it rather than exact.
+/// `ModInfo::from_json` has the final say.
+#[cfg(feature = "schemars")]
+fn version_req_pattern() -> String {
+    let part = "([*xX]|0|[1-9][0-9]*)";
+    let identifiers = r"[0-9A-Za-z-]+(\.[0-9A-Za-z-]+)*";
+    // Like `^1.2.3-beta`, `~1.2`, `1.*` or `*`
+    let comparator = format!(
+        r"(=|>=?|<=?|~|\^)?\s*{part}(\.{part}(\.{part}(-{identifiers})?(\+{identifiers})?)?)?"
+    );
+
+    format!(r"^\s*{comparator}(\s*,\s*{comparator})*\s*$")
+}
+
+#[cfg(feature = "schemars")]
+fn dependencies_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+    schemars::json_schema!({
+        "type": "object",
+        "additionalProperties": { "type": "string", "pattern": version_req_pattern() }
+    })
+}
+
+/// A mod directory inside of the mods folder
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadedMod {
+    /// The name of the mod's directory, which grug uses as the mod name in entities like `"hello_world:hello"`
+    pub dir_name: String,
+    /// The path to the mod's directory
+    pub path: PathBuf,
+    pub info: ModInfo,
+    /// Whether the mod's `game_version` is compatible with the game's version
+    pub compatibility: Compatibility,
+}
+
+/// Whether a mod can be used with the game's version
+#[derive(Debug, Clone, PartialEq)]
+pub enum Compatibility {
+    /// No game version was given, so nothing was checked
+    Unchecked,
+    Compatible,
+    /// The mod was made for a different version of the game
+    Incompatible,
+    /// The mod's `game_version` isn't a valid semver version or version requirement
+    Invalid {
+        error: String,
+    },
+}
+
+impl Compatibility {
+    /// Checks a mod's `game_version` against the game's version
+    ///
+    /// A plain version like `1.2.0` accepts every game version that is semver compatible with it,
+    /// so `1.2.0` up to but not including `2.0.0`.
+    /// Requirements like `>=1.2, <1.5` are also accepted.
+    ///
+    /// # Example
+    /// ```
+    /// use grug_rs::mod_info::Compatibility;
+    /// use grug_rs::semver::Version;
+    ///
+    /// let game_version = Version::new(1, 4, 0);
+    /// assert_eq!(Compatibility::check(&game_version, "1.2.0"), Compatibility::Compatible);
+    /// assert_eq!(Compatibility::check(&game_version, "2.0.0"), Compatibility::Incompatible);
+    /// ```
+    pub fn check(game_version: &Version, mod_game_version: &str) -> Self {
+        match VersionReq::parse(mod_game_version) {
+            Ok(requirement) if requirement.matches(game_version) => Self::Compatible,
+            Ok(_) => Self::Incompatible,
+            Err(error) => Self::Invalid {
+                error: error.to_string(),
+            },
+        }
+    }
+
+    /// Unchecked mods count as compatible
+    pub fn is_compatible(&self) -> bool {
+        matches!(self, Self::Unchecked | Self::Compatible)
+    }
+}
+
+/// What to do with mods that are incompatible with the game's version
+///
+/// Nothing is printed, `Grug::incompatible_mods` lists them so the game can warn however it likes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompatibilityPolicy {
+    /// Use the mod anyway
+    #[default]
+    Warn,
+    /// Never run the mod's files
+    ///
+    /// This only skips activating them. grug still compiles every mod in the mods folder,
+    /// so a skipped mod that fails to compile still makes regenerating fail,
+    /// with `GrugError::IncompatibleMod`, until it's removed from the mods folder.
+    Skip,
+    /// Fail to initialize with `GrugError::IncompatibleMods`
+    Refuse,
+}
+
+impl ModInfo {
+    /// Reads and validates the `about.json` of a mod directory
+    pub fn from_mod_dir<P: AsRef<Path>>(mod_dir: P) -> Result<Self, GrugError> {
+        let path = mod_dir.as_ref().join("about.json");
+
+        let json = read_to_string(&path).map_err(|x| GrugError::ModInfo {
+            path: path.clone(),
+            problems: vec![x.to_string()],
+        })?;
+
+        Self::from_json(&json).map_err(|problems| GrugError::ModInfo { path, problems })
+    }
+
+    /// Parses and validates the contents of an `about.json`
+    ///
+    /// `version` has to be a semver version and unknown fields aren't allowed, like in `schema::mod_info`.
+    /// Returns every problem that was found on failure.
+    ///
+    /// # Example
+    /// ```
+    /// use grug_rs::ModInfo;
+    ///
+    /// let problems = ModInfo::from_json(r#"{ "name": "foo", "version": 1 }"#).unwrap_err();
+    /// assert_eq!(
+    ///     problems,
+    ///     [
+    ///         "the `version` field must be a string",
+    ///         "missing the `game_version` field",
+    ///         "missing the `author` field",
+    ///     ]
+    /// );
+    /// ```
+    pub fn from_json(json: &str) -> Result<Self, Vec<String>> {
+        let value: Value = from_str(json).map_err(|x| vec![x.to_string()])?;
+
+        let Some(object) = value.as_object() else {
+            return Err(vec!["the root must be an object".to_string()]);
+        };
+
+        let mut problems = vec![];
+
+        let mut field = |field: Result<String, String>| match field {
+            Ok(value) => value,
+            Err(problem) => {
+                problems.push(problem);
+                String::new()
+            }
+        };
+
+        let mut info = Self {
+            name: field(string_field(object, "name")),
+            version: field(version_field(object)),
+            game_version: field(string_field(object, "game_version")),
+            author: field(string_field(object, "author")),
+            dependencies: LinkedHashMap::new(),
+        };
+
+        match dependencies_field(object) {
+            Ok(dependencies) => info.dependencies = dependencies,
+            Err(mut dependency_problems) => problems.append(&mut dependency_problems),
+        }
+
+        for name in object.keys() {
+            if !FIELDS.contains(&name.as_str()) {
+                problems.push(format!(
+                    "unknown field `{name}`, expected one of `{}`",
+                    FIELDS.join("`, `")
+                ));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(info)
+        } else {
+            Err(problems)
+        }
+    }
+}
+
+const FIELDS: [&str; 5] = ["name", "version", "game_version", "author", "dependencies"];
+
+fn string_field(object: &Map<String, Value>, name: &str) -> Result<String, String> {
+    match object.get(name) {
+        None => Err(format!("missing the `{name}` field")),
+        Some(Value::String(value)) if value.is_empty() => {
+            Err(format!("the `{name}` field must not be empty"))
+        }
+        Some(Value::String(value)) => Ok(value.clone()),
+        Some(_) => Err(format!("the `{name}` field must be a string")),
+    }
+}
+
+fn version_field(object: &Map<String, Value>) -> Result<String, String> {
+    let version = string_field(object, "version")?;
+
+    match Version::parse(&version) {
+        Ok(_) => Ok(version),
+        Err(error) => Err(format!(
+            "the `version` field is not a valid semver version: {error}"
+        )),
+    }
+}
+
+fn dependencies_field(
+    object: &Map<String, Value>,
+) -> Result<LinkedHashMap<String, String>, Vec<String>> {
+    let Some(value) = object.get("dependencies") else {
+        return Ok(LinkedHashMap::new());
+    };
+
+    let Some(dependencies) = value.as_object() else {
+        return Err(vec![
+            "the `dependencies` field must be an object".to_string(),
+        ]);
+    };
+
+    let mut problems = vec![];
+    let mut parsed = LinkedHashMap::new();
+
+    for (name, requirement) in dependencies {
+        match requirement.as_str().map(VersionReq::parse) {
+            Some(Ok(_)) => {
+                parsed.insert(name.clone(), requirement.as_str().unwrap().to_string());
+            }
+            Some(Err(error)) => problems.push(format!(
+                "the `dependencies.{name}` field is not a valid version requirement: {error}"
+            )),
+            None => problems.push(format!("the `dependencies.{name}` field must be a string")),
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(parsed)
+    } else {
+        Err(problems)
+    }
+}
+
+/// Reads the `about.json` of every mod directory in `mods_folder`, sorted by directory name
+///
+/// Checks the compatibility of every mod if `game_version` is given.
+#[cfg(feature = "runtime")]
+pub(crate) fn load_mods(
+    mods_folder: &Path,
+    game_version: Option<&Version>,
+) -> Result<Vec<LoadedMod>, GrugError> {
+    let entries = read_dir(mods_folder).map_err(|x| GrugError::ModsFolder {
+        path: mods_folder.to_path_buf(),
+        error: x.to_string(),
+    })?;
+
+    let mut mods = vec![];
+
+    for entry in entries {
+        let path = entry
+            .map_err(|x| GrugError::ModsFolder {
+                path: mods_folder.to_path_buf(),
+                error: x.to_string(),
+            })?
+            .path();
+
+        if !path.is_dir() {
+            continue;
+        }
+
+        let info = ModInfo::from_mod_dir(&path)?;
+        let compatibility = game_version.map_or(Compatibility::Unchecked, |game_version| {
+            Compatibility::check(game_version, &info.game_version)
+        });
+
+        mods.push(LoadedMod {
+            dir_name: path.file_name().unwrap().to_string_lossy().into_owned(),
+            info,
+            path,
+            compatibility,
+        });
+    }
+
+    mods.sort_by(|a, b| a.dir_name.cmp(&b.dir_name));
+
+    Ok(mods)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_json_parses_every_field() {
+        let info = ModInfo::from_json(
+            r#"{
+                "name": "Hello",
+                "version": "1.0.0",
+                "game_version": "1.2.0",
+                "author": "LambdaLemon",
+                "dependencies": { "shared_lib": "^1.2", "other": ">=0.3" }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(info.name, "Hello");
+        assert_eq!(info.version, "1.0.0");
+        assert_eq!(info.game_version, "1.2.0");
+        assert_eq!(info.author, "LambdaLemon");
+        assert_eq!(info.dependencies.len(), 2);
+        assert_eq!(info.dependencies["shared_lib"], "^1.2");
+        assert_eq!(info.dependencies["other"], ">=0.3");
+    }
+
+    #[test]
+    fn from_json_without_dependencies() {
+        let info = ModInfo::from_json(
+            r#"{ "name": "Hello", "version": "1.0.0", "game_version": "1.2.0", "author": "LambdaLemon" }"#,
+        )
+        .unwrap();
+
+        assert!(info.dependencies.is_empty());
+    }
+
+    #[test]
+    fn from_json_reports_every_problem() {
+        let problems = ModInfo::from_json(
+            r#"{
+                "name": "",
+                "version": 1,
+                "author": "LambdaLemon",
+                "dependencies": { "a": "not a version", "b": 2 }
+            }"#,
+        )
+        .unwrap_err();
+
+        assert_eq!(problems.len(), 5);
+        assert_eq!(problems[0], "the `name` field must not be empty");
+        assert_eq!(problems[1], "the `version` field must be a string");
+        assert_eq!(problems[2], "missing the `game_version` field");
+        assert!(
+            problems[3]
+                .starts_with("the `dependencies.a` field is not a valid version requirement: ")
+        );
+        assert_eq!(problems[4], "the `dependencies.b` field must be a string");
+    }
+
+    #[test]
+    fn from_json_rejects_unknown_fields() {
+        let problems = ModInfo::from_json(
+            r#"{ "name": "Hello", "version": "1.0.0", "game_version": "1.2.0", "author": "LambdaLemon", "dependecies": {} }"#,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            problems,
+            [
+                "unknown field `dependecies`, expected one of `name`, `version`, `game_version`, `author`, `dependencies`"
+            ]
+        );
+    }
+
+    #[test]
+    fn from_json_rejects_versions_that_arent_semver() {
+        let version = |version: &str| {
+            ModInfo::from_json(&format!(
+                r#"{{ "name": "Hello", "version": "{version}", "game_version": "1.2.0", "author": "LambdaLemon" }}"#
+            ))
+        };
+
+        assert!(version("1.0.0").is_ok());
+        assert!(version("1.0.0-beta.1+build").is_ok());
+
+        for invalid in ["one", "1.0", "^1.0.0", "v1.0.0"] {
+            let problems = version(invalid).unwrap_err();
+
+            assert_eq!(problems.len(), 1, "{invalid}");
+            assert!(
+                problems[0].starts_with("the `version` field is not a valid semver version: "),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn from_json_rejects_dependencies_that_arent_an_object() {
+        let problems = ModInfo::from_json(
+            r#"{ "name": "Hello", "version": "1.0.0", "game_version": "1.2.0", "author": "LambdaLemon", "dependencies": [] }"#,
+        )
+        .unwrap_err();
+
+        assert_eq!(problems, ["the `dependencies` field must be an object"]);
+    }
+
+    #[test]
+    fn from_json_rejects_a_root_that_isnt_an_object() {
+        assert_eq!(
+            ModInfo::from_json("[]").unwrap_err(),
+            ["the root must be an object"]
+        );
+    }
+
+    #[test]
+    fn from_json_rejects_invalid_json() {
+        assert_eq!(ModInfo::from_json("{").unwrap_err().len(), 1);
+    }
+
+    #[test]
+    fn check_plain_versions_accept_semver_compatible_games() {
+        let check = |game_version: &str, mod_game_version| {
+            Compatibility::check(&Version::parse(game_version).unwrap(), mod_game_version)
+        };
+
+        assert_eq!(check("1.2.0", "1.2.0"), Compatibility::Compatible);
+        assert_eq!(check("1.9.3", "1.2.0"), Compatibility::Compatible);
+        assert_eq!(check("1.1.0", "1.2.0"), Compatibility::Incompatible);
+        assert_eq!(check("2.0.0", "1.2.0"), Compatibility::Incompatible);
+        // Before 1.0.0 every minor version is breaking
+        assert_eq!(check("0.3.1", "0.3.0"), Compatibility::Compatible);
+        assert_eq!(check("0.4.0", "0.3.0"), Compatibility::Incompatible);
+    }
+
+    #[test]
+    fn check_requirements() {
+        let game_version = Version::new(1, 4, 0);
+
+        assert_eq!(
+            Compatibility::check(&game_version, ">=1.2, <1.5"),
+            Compatibility::Compatible
+        );
+        assert_eq!(
+            Compatibility::check(&game_version, ">=1.5"),
+            Compatibility::Incompatible
+        );
+        assert_eq!(
+            Compatibility::check(&game_version, "=1.4.0"),
+            Compatibility::Compatible
+        );
+    }
+
+    #[test]
+    fn check_invalid_versions() {
+        assert!(matches!(
+            Compatibility::check(&Version::new(1, 0, 0), "one"),
+            Compatibility::Invalid { .. }
+        ));
+    }
+
+    #[test]
+    fn only_unchecked_and_compatible_are_compatible() {
+        assert!(Compatibility::Unchecked.is_compatible());
+        assert!(Compatibility::Compatible.is_compatible());
+        assert!(!Compatibility::Incompatible.is_compatible());
+        assert!(
+            !Compatibility::Invalid {
+                error: String::new()
+            }
+            .is_compatible()
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use std::{
+    fs::{read_to_string, write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{from_str, to_string_pretty};
+
+use crate::{GrugError, LoadedMod};
+
+/// Which mods are enabled and in what order they're loaded
+///
+/// Mods are referred to by their directory name.
+/// Mods that aren't listed are enabled and loaded after the listed ones, sorted by name.
+///
+/// # Example
+/// ```json
+/// {
+///   "mods": [
+///     { "name": "base_game" },
+///     { "name": "hard_mode", "enabled": false }
+///   ]
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ModProfile {
+    /// Mods in load order, earlier mods are loaded first
+    #[serde(default)]
+    pub mods: Vec<ModProfileEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ModProfileEntry {
+    pub name: String,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl ModProfile {
+    /// A profile that enables every mod, in the order they're given
+    pub fn from_mods(mods: &[LoadedMod]) -> Self {
+        Self {
+            mods: mods
+                .iter()
+                .map(|mod_| ModProfileEntry {
+                    name: mod_.dir_name.clone(),
+                    enabled: true,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, GrugError> {
+        let path = path.as_ref();
+
+        let json = read_to_string(path).map_err(|x| GrugError::ModProfile {
+            path: path.to_path_buf(),
+            error: x.to_string(),
+        })?;
+
+        from_str(&json).map_err(|x| GrugError::ModProfile {
+            path: path.to_path_buf(),
+            error: x.to_string(),
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), GrugError> {
+        let path = path.as_ref();
+
+        write(path, to_string_pretty(self).unwrap()).map_err(|x| GrugError::ModProfile {
+            path: path.to_path_buf(),
+            error: x.to_string(),
+        })
+    }
+
+    /// Whether a mod is enabled, mods that aren't listed are enabled
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.entry(name).is_none_or(|entry| entry.enabled)
+    }
+
+    /// Enables or disables a mod, adding it to the end of the list if it isn't listed
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) {
+        match self.mods.iter_mut().find(|entry| entry.name == name) {
+            Some(entry) => entry.enabled = enabled,
+            None => self.mods.push(ModProfileEntry {
+                name: name.to_string(),
+                enabled,
+            }),
+        }
+    }
+
+    /// The position of a mod in the load order, `None` if it isn't listed
+    pub fn priority(&self, name: &str) -> Option<usize> {
+        self.mods.iter().position(|entry| entry.name == name)
+    }
+
+    /// Moves a mod to `index` in the load order, adding it if it isn't listed
+    pub fn move_to(&mut self, name: &str, index: usize) {
+        let entry = match self.priority(name) {
+            Some(priority) => self.mods.remove(priority),
+            None => ModProfileEntry {
+                name: name.to_string(),
+                enabled: true,
+            },
+        };
+
+        self.mods.insert(index.min(self.mods.len()), entry);
+    }
+
+    fn entry(&self, name: &str) -> Option<&ModProfileEntry> {
+        self.mods.iter().find(|entry| entry.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(names: &[&str]) -> ModProfile {
+        ModProfile {
+            mods: names
+                .iter()
+                .map(|name| ModProfileEntry {
+                    name: name.to_string(),
+                    enabled: true,
+                })
+                .collect(),
+        }
+    }
+
+    fn names(profile: &ModProfile) -> Vec<&str> {
+        profile
+            .mods
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn move_to_earlier() {
+        let mut profile = profile(&["a", "b", "c"]);
+        profile.move_to("c", 0);
+        assert_eq!(names(&profile), ["c", "a", "b"]);
+    }
+
+    #[test]
+    fn move_to_later() {
+        let mut profile = profile(&["a", "b", "c"]);
+        profile.move_to("a", 1);
+        assert_eq!(names(&profile), ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn move_to_past_the_end_goes_last() {
+        let mut profile = profile(&["a", "b", "c"]);
+        profile.move_to("a", 10);
+        assert_eq!(names(&profile), ["b", "c", "a"]);
+    }
+
+    #[test]
+    fn move_to_adds_unlisted_mods() {
+        let mut profile = profile(&["a", "b"]);
+        profile.move_to("new", 1);
+        assert_eq!(names(&profile), ["a", "new", "b"]);
+        assert!(profile.is_enabled("new"));
+    }
+
+    #[test]
+    fn move_to_keeps_disabled_mods_disabled() {
+        let mut profile = profile(&["a", "b"]);
+        profile.set_enabled("a", false);
+        profile.move_to("a", 1);
+        assert_eq!(names(&profile), ["b", "a"]);
+        assert!(!profile.is_enabled("a"));
+    }
+
+    #[test]
+    fn set_enabled_adds_unlisted_mods_last() {
+        let mut profile = profile(&["a"]);
+        profile.set_enabled("b", false);
+        assert_eq!(names(&profile), ["a", "b"]);
+        assert_eq!(profile.priority("b"), Some(1));
+        assert!(!profile.is_enabled("b"));
+        assert!(profile.is_enabled("unlisted"));
+    }
+
+    #[test]
+    fn enabled_defaults_to_true() {
+        let profile: ModProfile =
+            from_str(r#"{ "mods": [{ "name": "a" }, { "name": "b", "enabled": false }] }"#)
+                .unwrap();
+        assert!(profile.is_enabled("a"));
+        assert!(!profile.is_enabled("b"));
+    }
+}
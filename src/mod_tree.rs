@@ -0,0 +1,495 @@
+use std::{
+    ffi::{CStr, c_char, c_void},
+    path::{Path, PathBuf},
+    slice::from_raw_parts,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+};
+
+use grug_sys::*;
+use seq_macro::seq;
+
+use crate::{GrugError, OpaqueGrugType, mod_api_type::ModAPI};
+
+/// A snapshot of a directory in the loaded mod tree
+///
+/// The root directory is the mods folder itself, its sub-directories are the mods.
+///
+/// # Example
+/// ```no_run
+/// use grug_rs::Grug;
+///
+/// # fn main() -> Result<(), grug_rs::GrugError> {
+/// let grug: Grug = todo!();
+/// grug.regenerate_modified_mods()?;
+///
+/// for file in grug.mod_tree().walk_files() {
+///     println!("{} ({})", file.entity(), file.relative_path().display());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ModDir {
+    name: String,
+    relative_path: PathBuf,
+    dirs: Vec<ModDir>,
+    files: Vec<GrugFile>,
+}
+
+impl ModDir {
+    /// Copies `dir` and everything inside of it
+    ///
+    /// `mod_api` is used to record which `on_functions` each file defines.
+    ///
+    /// # Safety
+    /// `dir` has to come from grug and grug must not have regenerated since
+    pub(crate) unsafe fn from_raw(
+        dir: &grug_mod_dir,
+        relative_path: PathBuf,
+        mod_api: &ModAPI,
+    ) -> Self {
+        let raw_dirs = unsafe { raw_slice(dir.dirs, dir.dirs_size) };
+        let raw_files = unsafe { raw_slice(dir.files, dir.files_size) };
+
+        let dirs = raw_dirs
+            .iter()
+            .map(|sub_dir| {
+                let path = relative_path.join(unsafe { c_string(sub_dir.name) });
+                unsafe { Self::from_raw(sub_dir, path, mod_api) }
+            })
+            .collect();
+
+        let files = raw_files
+            .iter()
+            .map(|file| unsafe { GrugFile::in_dir(*file, &relative_path, mod_api) })
+            .collect();
+
+        Self {
+            name: unsafe { c_string(dir.name) },
+            relative_path,
+            dirs,
+            files,
+        }
+    }
+
+    /// The name of the directory
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The path relative to the mods folder, empty for the mods folder itself
+    pub fn relative_path(&self) -> &Path {
+        &self.relative_path
+    }
+
+    /// The name of the mod this directory is in, `None` for the mods folder itself
+    pub fn mod_name(&self) -> Option<&str> {
+        self.relative_path
+            .components()
+            .next()
+            .and_then(|component| component.as_os_str().to_str())
+    }
+
+    /// The directories directly inside of this one
+    pub fn dirs(&self) -> &[ModDir] {
+        &self.dirs
+    }
+
+    /// The grug files directly inside of this directory
+    pub fn files(&self) -> &[GrugFile] {
+        &self.files
+    }
+
+    /// Every directory inside of this one, recursively, parents before their children
+    pub fn walk_dirs(&self) -> Box<dyn Iterator<Item = &ModDir> + '_> {
+        Box::new(
+            self.dirs
+                .iter()
+                .flat_map(|dir| std::iter::once(dir).chain(dir.walk_dirs())),
+        )
+    }
+
+    /// Every grug file inside of this directory, recursively, files before sub-directories
+    pub fn walk_files(&self) -> Box<dyn Iterator<Item = &GrugFile> + '_> {
+        Box::new(
+            self.files
+                .iter()
+                .chain(self.dirs.iter().flat_map(|dir| dir.walk_files())),
+        )
+    }
+}
+
+/// Bumped whenever grug's mod tree may have changed, so copies of it know they're outdated
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+/// The amount of directories and files in grug's mod tree as of the last regeneration
+static DIRS: AtomicUsize = AtomicUsize::new(0);
+static FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// The current generation of grug's mod tree, see `track_changes`
+pub(crate) fn generation() -> u64 {
+    GENERATION.load(Ordering::Acquire)
+}
+
+/// Bumps the generation if the last regeneration failed, reloaded a file, or added or removed any
+///
+/// Files that are added are compiled and reloaded, so only removals have to be counted.
+///
+/// # Safety
+/// Has to be called right after regenerating, and grug must not regenerate at the same time
+pub(crate) unsafe fn track_changes(failed: bool) {
+    #[allow(static_mut_refs)]
+    let (mods, reloads) = unsafe { (&grug_mods, grug_reloads_size) };
+    let (dirs, files) = unsafe { count(mods) };
+
+    let dirs_changed = DIRS.swap(dirs, Ordering::AcqRel) != dirs;
+    let files_changed = FILES.swap(files, Ordering::AcqRel) != files;
+
+    if failed || reloads > 0 || dirs_changed || files_changed {
+        GENERATION.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+/// The amount of directories and files inside of `dir`, recursively, without copying anything
+unsafe fn count(dir: &grug_mod_dir) -> (usize, usize) {
+    unsafe { raw_slice(dir.dirs, dir.dirs_size) }
+        .iter()
+        .map(|sub_dir| unsafe { count(sub_dir) })
+        .fold(
+            (dir.dirs_size, dir.files_size),
+            |(dirs, files), (sub_dirs, sub_files)| (dirs + sub_dirs, files + sub_files),
+        )
+}
+
+/// Like `from_raw_parts`, but allows a null pointer when there are no elements
+unsafe fn raw_slice<'a, T>(ptr: *const T, len: usize) -> &'a [T] {
+    if ptr.is_null() || len == 0 {
+        &[]
+    } else {
+        unsafe { from_raw_parts(ptr, len) }
+    }
+}
+
+unsafe fn c_string(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(ptr).to_string_lossy().into_owned() }
+    }
+}
+
+/// # Safety
+/// `file.on_fns` has to be null or have more than `index` entries
+unsafe fn on_fn_is_defined(file: &grug_file, index: usize) -> bool {
+    let on_fns = file.on_fns as *const Option<unsafe extern "C" fn(*mut c_void)>;
+
+    !on_fns.is_null() && unsafe { (*on_fns.add(index)).is_some() }
+}
+
+/// A snapshot of a grug file in the loaded mod tree
+#[derive(Clone)]
+pub struct GrugFile {
+    /// Only valid until grug regenerates, so it's only read right after regenerating
+    inner: grug_file,
+    relative_path: PathBuf,
+    entity: String,
+    entity_type: String,
+    /// Copied along with the rest, since `inner.on_fns` can dangle by the time they're asked for
+    defined_on_functions: Vec<String>,
+}
+
+impl GrugFile {
+    /// # Safety
+    /// The strings of `file` have to be valid, and `file.on_fns` has to be null
+    /// or have an entry for every `on_function` of its entity type in `mod_api`
+    pub(crate) unsafe fn in_dir(file: grug_file, dir: &Path, mod_api: &ModAPI) -> Self {
+        let entity_type = unsafe { c_string(file.entity_type) };
+
+        let defined_on_functions = match mod_api.entities.get(&entity_type) {
+            Some(entity) => entity
+                .on_functions
+                .keys()
+                .enumerate()
+                .filter(|&(index, _)| unsafe { on_fn_is_defined(&file, index) })
+                .map(|(_, name)| name.clone())
+                .collect(),
+            None => vec![],
+        };
+
+        Self {
+            inner: file,
+            relative_path: dir.join(unsafe { c_string(file.name) }),
+            entity: unsafe { c_string(file.entity) },
+            entity_type,
+            defined_on_functions,
+        }
+    }
+
+    /// The path relative to the mods folder, like `hello_world/hello-World.grug`
+    pub fn relative_path(&self) -> &Path {
+        &self.relative_path
+    }
+
+    /// The file name, like `hello-World.grug`
+    pub fn file_name(&self) -> &str {
+        self.relative_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default()
+    }
+
+    /// The name of the mod the file is in, like `hello_world`
+    pub fn mod_name(&self) -> &str {
+        self.entity
+            .split_once(':')
+            .map_or("", |(mod_name, _)| mod_name)
+    }
+
+    /// The entity including its mod, like `hello_world:hello`
+    pub fn entity(&self) -> &str {
+        &self.entity
+    }
+
+    /// The entity without its mod, like `hello`
+    pub fn entity_name(&self) -> &str {
+        self.entity
+            .split_once(':')
+            .map_or(self.entity.as_str(), |(_, name)| name)
+    }
+
+    /// The entity type, like `World`
+    pub fn entity_type(&self) -> &str {
+        &self.entity_type
+    }
+
+    /// The names of the `on_functions` this file defines, in `mod_api.json` order
+    ///
+    /// As of when the snapshot was taken, so it stays the same after the file is reloaded.
+    pub fn defined_on_functions(&self) -> &[String] {
+        &self.defined_on_functions
+    }
+
+    /// # Safety
+    /// `index` has to be below the amount of `on_functions` of the entity type,
+    /// and grug must not have regenerated since the file was copied
+    pub(crate) unsafe fn has_on_function(&self, index: usize) -> bool {
+        unsafe { on_fn_is_defined(&self.inner, index) }
+    }
+
+    /// # SAFETY
+    /// Will segfault if you put an invalid index.
+    ///
+    /// Assumes `arguments` is non-null.
+    ///
+    /// Grug must not have regenerated since the file was copied, so get it again after regenerating.
+    pub unsafe fn run_on_function(
+        &self,
+        index: usize,
+        arguments: *mut *mut c_void,
+        arguments_len: usize,
+    ) -> Result<(), GrugError> {
+        if unsafe { !self.has_on_function(index) } {
+            // Ensure the function actually has a definition
+            return Err(GrugError::UndefinedFunction);
+        }
+
+        // Every on_function gets the globals, including ones without arguments.
+        // `u64`s keep them aligned, and an empty `Vec` doesn't allocate when there are none
+        // Freed once the on_function returns
+        let mut buffer = vec![0u64; self.inner.globals_size.div_ceil(size_of::<u64>())];
+        let globals = buffer.as_mut_ptr();
+        unsafe { (self.inner.init_globals_fn.unwrap())(globals as *mut c_void, 0) };
+
+        let func =
+            unsafe { (self.inner.on_fns as *mut unsafe extern "C" fn(*mut c_void)).add(index) };
+
+        unsafe {
+            let args = from_raw_parts(arguments, arguments_len);
+            seq!(N in 1..3 {
+                match arguments_len {
+                    0 => (*func)(globals as *mut c_void),
+                    #(N => {
+                        seq!(M in 0..N {
+                            let func = func as *mut unsafe extern "C" fn(*mut c_void, #(OpaqueGrugType,)*);
+                            (*func)(globals as *mut c_void, #(*(args[M] as *mut _),)*);
+                        });
+                    },)*
+                    _ => panic!("Too many arguments, either report this or refactor."),
+                }
+            })
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr::null_mut;
+
+    use super::*;
+    use crate::mod_api_type::GameFunction;
+
+    fn file(name: &'static CStr, entity: &'static CStr) -> grug_file {
+        let mut file: grug_file = unsafe { std::mem::zeroed() };
+        file.name = name.as_ptr();
+        file.entity = entity.as_ptr();
+        file.entity_type = c"Enemy".as_ptr();
+        file
+    }
+
+    fn dir(
+        name: &'static CStr,
+        dirs: &mut [grug_mod_dir],
+        files: &mut [grug_file],
+    ) -> grug_mod_dir {
+        let mut dir: grug_mod_dir = unsafe { std::mem::zeroed() };
+        dir.name = name.as_ptr();
+        dir.dirs = if dirs.is_empty() {
+            null_mut()
+        } else {
+            dirs.as_mut_ptr()
+        };
+        dir.dirs_size = dirs.len();
+        dir.files = if files.is_empty() {
+            null_mut()
+        } else {
+            files.as_mut_ptr()
+        };
+        dir.files_size = files.len();
+        dir
+    }
+
+    /// Calls `f` with the same tree as `examples/nested_mods`
+    fn with_nested_mods(f: impl FnOnce(&grug_mod_dir)) {
+        let mut dragon = [file(c"dragon-Enemy.grug", c"base:dragon")];
+        let mut bosses = [dir(c"bosses", &mut [], &mut dragon)];
+        let mut goblin = [file(c"goblin-Enemy.grug", c"base:goblin")];
+        let mut enemies = [dir(c"enemies", &mut bosses, &mut goblin)];
+        let mut slime = [file(c"slime-Enemy.grug", c"base:slime")];
+        let mut bat = [file(c"bat-Enemy.grug", c"extra:bat")];
+        let mut mods = [
+            dir(c"base", &mut enemies, &mut slime),
+            dir(c"extra", &mut [], &mut bat),
+        ];
+
+        f(&dir(c"mods", &mut mods, &mut []));
+    }
+
+    #[test]
+    fn from_raw_copies_nested_directories() {
+        with_nested_mods(|raw| {
+            let root = unsafe { ModDir::from_raw(raw, PathBuf::new(), &ModAPI::default()) };
+
+            assert_eq!(root.name(), "mods");
+            assert_eq!(root.mod_name(), None);
+            assert!(root.files().is_empty());
+
+            let dirs: Vec<(&Path, Option<&str>)> = root
+                .walk_dirs()
+                .map(|dir| (dir.relative_path(), dir.mod_name()))
+                .collect();
+            assert_eq!(
+                dirs,
+                [
+                    (Path::new("base"), Some("base")),
+                    (Path::new("base/enemies"), Some("base")),
+                    (Path::new("base/enemies/bosses"), Some("base")),
+                    (Path::new("extra"), Some("extra")),
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn walk_files_goes_through_sub_directories() {
+        with_nested_mods(|raw| {
+            let root = unsafe { ModDir::from_raw(raw, PathBuf::new(), &ModAPI::default()) };
+
+            let files: Vec<(&Path, &str, &str, &str)> = root
+                .walk_files()
+                .map(|file| {
+                    (
+                        file.relative_path(),
+                        file.file_name(),
+                        file.mod_name(),
+                        file.entity_name(),
+                    )
+                })
+                .collect();
+            assert_eq!(
+                files,
+                [
+                    (
+                        Path::new("base/slime-Enemy.grug"),
+                        "slime-Enemy.grug",
+                        "base",
+                        "slime"
+                    ),
+                    (
+                        Path::new("base/enemies/goblin-Enemy.grug"),
+                        "goblin-Enemy.grug",
+                        "base",
+                        "goblin"
+                    ),
+                    (
+                        Path::new("base/enemies/bosses/dragon-Enemy.grug"),
+                        "dragon-Enemy.grug",
+                        "base",
+                        "dragon"
+                    ),
+                    (
+                        Path::new("extra/bat-Enemy.grug"),
+                        "bat-Enemy.grug",
+                        "extra",
+                        "bat"
+                    ),
+                ]
+            );
+            assert!(root.walk_files().all(|file| file.entity_type() == "Enemy"));
+        });
+    }
+
+    #[test]
+    fn count_includes_sub_directories() {
+        with_nested_mods(|raw| assert_eq!(unsafe { count(raw) }, (4, 4)));
+    }
+
+    #[test]
+    fn files_without_on_functions_have_none() {
+        let file = unsafe {
+            GrugFile::in_dir(
+                file(c"bat-Enemy.grug", c"extra:bat"),
+                Path::new("extra"),
+                &enemy_api(),
+            )
+        };
+
+        assert!(unsafe { !file.has_on_function(0) });
+        assert!(file.defined_on_functions().is_empty());
+    }
+
+    fn enemy_api() -> ModAPI {
+        ModAPI::builder()
+            .entity("Enemy", "Something to fight")
+            .on_function("on_spawn", GameFunction::new("Called once"))
+            .on_function("on_update", GameFunction::new("Called every tick"))
+            .on_function("on_despawn", GameFunction::new("Called last"))
+            .build()
+    }
+
+    unsafe extern "C" fn on_fn(_globals: *mut c_void) {}
+
+    #[test]
+    fn defined_on_functions_outlive_on_fns() {
+        let mut on_fns: Vec<Option<unsafe extern "C" fn(*mut c_void)>> =
+            vec![Some(on_fn), None, Some(on_fn)];
+        let mut raw = file(c"goblin-Enemy.grug", c"base:goblin");
+        raw.on_fns = on_fns.as_mut_ptr() as *mut c_void;
+
+        let file = unsafe { GrugFile::in_dir(raw, Path::new("base"), &enemy_api()) };
+        // Like grug freeing them when it regenerates
+        drop(on_fns);
+
+        assert_eq!(file.defined_on_functions(), ["on_spawn", "on_despawn"]);
+    }
+}
@@ -0,0 +1,385 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    ffi::{CStr, c_char},
+    fmt::Display,
+    ops::Deref,
+    path::{Component, Path, PathBuf},
+    sync::{Arc, LazyLock, RwLock},
+};
+
+use crate::mod_api_type::{GrugType, ModAPI};
+
+/// A file inside of a mod, given to a game function with a `resource` argument
+///
+/// # Example
+/// ```no_run
+/// # #![deny(improper_ctypes_definitions)]
+/// use grug_rs::resource::Resource;
+/// use grug_rs_proc_macro::game_function;
+///
+/// #[game_function]
+/// fn play_sound(sound: Resource) {
+///     println!("Playing {} from {}", sound.relative_path().display(), sound.mod_name());
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Resource {
+    path: PathBuf,
+    mod_name: String,
+    relative_path: PathBuf,
+}
+
+impl Resource {
+    /// Resolves the resource grug passes to a game function,
+    /// checking it against the `resource_extension` of the argument
+    ///
+    /// Used by `#[game_function]`.
+    ///
+    /// # Safety
+    /// `raw` has to be a valid C string
+    #[doc(hidden)]
+    pub unsafe fn from_game_function(
+        raw: *const c_char,
+        game_function: &str,
+        argument: usize,
+    ) -> Result<Self, String> {
+        if raw.is_null() {
+            return Err(format!("`{game_function}` was given a null resource"));
+        }
+
+        let raw = unsafe { CStr::from_ptr(raw) }.to_string_lossy();
+
+        CONTEXT
+            .read()
+            .unwrap()
+            .resolve(&raw, game_function, argument)
+    }
+
+    /// The absolute path of the file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The mod the resource is in
+    pub fn mod_name(&self) -> &str {
+        &self.mod_name
+    }
+
+    /// The path relative to the mod, like it's written in grug
+    pub fn relative_path(&self) -> &Path {
+        &self.relative_path
+    }
+}
+
+/// What resources are resolved with, set when grug is initialized
+#[derive(Default)]
+struct Context {
+    mods_folder: PathBuf,
+    /// The `resource_extension` of every `(game function, argument index)`
+    extensions: HashMap<(String, usize), String>,
+}
+
+impl Context {
+    /// Turns the path grug gives a game function into a `Resource`
+    fn resolve(&self, raw: &str, game_function: &str, argument: usize) -> Result<Resource, String> {
+        // grug passes resources as `{mods folder}/{mod}/{resource}`
+        let in_mods_folder = Path::new(raw)
+            .strip_prefix(&self.mods_folder)
+            .map_err(|_| format!("The resource `{raw}` isn't inside of the mods folder"))?;
+
+        let mut components = in_mods_folder.components();
+        let mod_name = match components.next() {
+            Some(Component::Normal(mod_name)) => mod_name.to_string_lossy().to_string(),
+            _ => return Err(format!("The resource `{raw}` isn't inside of a mod")),
+        };
+        let relative_path = components.as_path().to_path_buf();
+
+        if relative_path.as_os_str().is_empty()
+            || !relative_path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(format!("The resource `{raw}` isn't inside of `{mod_name}`"));
+        }
+
+        if let Some(extension) = self.extensions.get(&(game_function.to_string(), argument))
+            && Path::new(raw).extension().and_then(|x| x.to_str())
+                != Some(extension.trim_start_matches('.'))
+        {
+            return Err(format!(
+                "The resource `{raw}` given to `{game_function}` doesn't have the extension `{extension}`"
+            ));
+        }
+
+        let path = std::path::absolute(raw).map_err(|x| x.to_string())?;
+
+        Ok(Resource {
+            path,
+            mod_name,
+            relative_path,
+        })
+    }
+}
+
+static CONTEXT: LazyLock<RwLock<Context>> = LazyLock::new(RwLock::default);
+
+pub(crate) fn set_context(mods_folder: &Path, mod_api: &ModAPI) {
+    let extensions = mod_api
+        .game_functions
+        .iter()
+        .flat_map(|(name, game_function)| {
+            game_function
+                .arguments
+                .iter()
+                .enumerate()
+                .filter_map(move |(i, argument)| match &argument.type_ {
+                    GrugType::Resource { extension } => {
+                        Some(((name.clone(), i), extension.clone()))
+                    }
+                    _ => None,
+                })
+        })
+        .collect();
+
+    *CONTEXT.write().unwrap() = Context {
+        mods_folder: mods_folder.to_path_buf(),
+        extensions,
+    };
+}
+
+/// A loaded resource, shared between every game function call that loads the same file
+#[derive(Debug)]
+pub struct Asset<T>(Arc<T>);
+
+impl<T> Clone for Asset<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Deref for Asset<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+type Loader = Arc<dyn Fn(&Resource) -> Result<Arc<dyn Any + Send + Sync>, String> + Send + Sync>;
+
+#[derive(Default)]
+struct Loaders {
+    loaders: HashMap<TypeId, Loader>,
+    cache: HashMap<(TypeId, PathBuf), Arc<dyn Any + Send + Sync>>,
+}
+
+static LOADERS: LazyLock<RwLock<Loaders>> = LazyLock::new(RwLock::default);
+
+/// Registers how to load a `T` from a resource,
+/// so game functions can take an `Asset<T>` instead of a `Resource`
+///
+/// Every file is loaded once, until `clear_assets` is called.
+///
+/// # Example
+/// ```no_run
+/// # #![deny(improper_ctypes_definitions)]
+/// use grug_rs::resource::{Asset, register_loader};
+/// use grug_rs_proc_macro::game_function;
+///
+/// struct Text(String);
+///
+/// register_loader(|resource| std::fs::read_to_string(resource.path()).map(Text));
+///
+/// #[game_function]
+/// fn say(text: Asset<Text>) {
+///     println!("{}", text.0);
+/// }
+/// ```
+pub fn register_loader<T, E, F>(loader: F)
+where
+    T: Any + Send + Sync,
+    E: Display,
+    F: Fn(&Resource) -> Result<T, E> + Send + Sync + 'static,
+{
+    let loader: Loader = Arc::new(move |resource| {
+        loader(resource)
+            .map(|asset| Arc::new(asset) as Arc<dyn Any + Send + Sync>)
+            .map_err(|x| x.to_string())
+    });
+
+    LOADERS
+        .write()
+        .unwrap()
+        .loaders
+        .insert(TypeId::of::<T>(), loader);
+}
+
+/// Loads a resource with the loader registered for `T`, or gets it from the cache
+pub fn load<T: Any + Send + Sync>(resource: &Resource) -> Result<Asset<T>, String> {
+    let key = (TypeId::of::<T>(), resource.path.clone());
+
+    if let Some(asset) = LOADERS.read().unwrap().cache.get(&key) {
+        return Ok(Asset(asset.clone().downcast().unwrap()));
+    }
+
+    // The loader is called without holding the lock,
+    // so it can load the resources it depends on, and panicking doesn't poison it
+    let Some(loader) = LOADERS
+        .read()
+        .unwrap()
+        .loaders
+        .get(&TypeId::of::<T>())
+        .cloned()
+    else {
+        return Err(format!(
+            "No loader is registered for `{}`",
+            std::any::type_name::<T>()
+        ));
+    };
+
+    let asset = loader(resource).map_err(|x| {
+        format!(
+            "Failed to load `{}` from `{}`: {x}",
+            resource.relative_path.display(),
+            resource.mod_name
+        )
+    })?;
+
+    // Another thread could have loaded it in the meantime, so keep the asset that got there first
+    let asset = LOADERS
+        .write()
+        .unwrap()
+        .cache
+        .entry(key)
+        .or_insert(asset)
+        .clone();
+
+    Ok(Asset(asset.downcast().unwrap()))
+}
+
+/// Forgets every loaded asset, so they get loaded again the next time they're used
+pub fn clear_assets() {
+    LOADERS.write().unwrap().cache.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn context() -> Context {
+        Context {
+            mods_folder: PathBuf::from("mods"),
+            extensions: HashMap::from([(("draw".to_string(), 0), ".png".to_string())]),
+        }
+    }
+
+    fn resource(raw: &str) -> Resource {
+        context().resolve(raw, "play", 0).unwrap()
+    }
+
+    #[test]
+    fn strips_the_mods_folder() {
+        let resource = resource("mods/animals/sounds/moo.wav");
+
+        assert_eq!(resource.mod_name(), "animals");
+        assert_eq!(resource.relative_path(), Path::new("sounds/moo.wav"));
+        assert!(resource.path().is_absolute());
+        assert!(resource.path().ends_with("mods/animals/sounds/moo.wav"));
+    }
+
+    #[test]
+    fn stays_inside_of_the_mod() {
+        let context = context();
+
+        for raw in [
+            "elsewhere/animals/moo.wav",
+            "mods/animals",
+            "mods/animals/../plants/moo.wav",
+            "mods/animals/sounds/../../moo.wav",
+        ] {
+            assert!(context.resolve(raw, "play", 0).is_err(), "{raw}");
+        }
+    }
+
+    #[test]
+    fn checks_the_extension() {
+        let context = context();
+
+        assert!(context.resolve("mods/animals/cow.png", "draw", 0).is_ok());
+        assert!(context.resolve("mods/animals/cow.apng", "draw", 0).is_err());
+        assert!(context.resolve("mods/animals/png", "draw", 0).is_err());
+        assert!(
+            context
+                .resolve("mods/animals/cow.png.txt", "draw", 0)
+                .is_err()
+        );
+
+        // Only the argument with a `resource_extension` is checked
+        assert!(context.resolve("mods/animals/cow.apng", "draw", 1).is_ok());
+    }
+
+    #[test]
+    fn needs_a_loader() {
+        #[derive(Debug)]
+        struct Unregistered;
+
+        let error = load::<Unregistered>(&resource("mods/animals/moo.wav")).unwrap_err();
+
+        assert!(error.contains("Unregistered"), "{error}");
+    }
+
+    #[test]
+    fn loads_every_file_once() {
+        static LOADED: AtomicUsize = AtomicUsize::new(0);
+        #[derive(Debug)]
+        struct Counted(PathBuf);
+
+        register_loader(|resource| {
+            LOADED.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, String>(Counted(resource.relative_path().to_path_buf()))
+        });
+
+        let moo = load::<Counted>(&resource("mods/animals/moo.wav")).unwrap();
+        let again = load::<Counted>(&resource("mods/animals/moo.wav")).unwrap();
+        let oink = load::<Counted>(&resource("mods/animals/oink.wav")).unwrap();
+
+        assert!(Arc::ptr_eq(&moo.0, &again.0));
+        assert_eq!(oink.0.0, Path::new("oink.wav"));
+        assert_eq!(LOADED.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn loaders_can_load_what_they_depend_on() {
+        struct Sound;
+        struct Song;
+
+        register_loader(|_| Ok::<_, String>(Sound));
+        register_loader(|resource| load::<Sound>(resource).map(|_| Song));
+
+        assert!(load::<Song>(&resource("mods/animals/song.wav")).is_ok());
+    }
+
+    #[test]
+    fn failing_loaders_are_reported_and_dont_break_loading() {
+        #[derive(Debug)]
+        struct Broken;
+        struct Panicking;
+        struct Working;
+
+        register_loader(|_| Err::<Broken, _>("corrupted"));
+        register_loader(|_| -> Result<Panicking, String> { panic!("loader panicked") });
+        register_loader(|_| Ok::<_, String>(Working));
+
+        let error = load::<Broken>(&resource("mods/animals/moo.wav")).unwrap_err();
+        assert_eq!(error, "Failed to load `moo.wav` from `animals`: corrupted");
+
+        let panicked =
+            std::panic::catch_unwind(|| load::<Panicking>(&resource("mods/animals/moo.wav")));
+        assert!(panicked.is_err());
+
+        assert!(load::<Working>(&resource("mods/animals/moo.wav")).is_ok());
+    }
+}
@@ -0,0 +1,434 @@
+//! JSON Schemas of `mod_api.json` and `about.json`, so editors can check them while they're typed
+//!
+//! Point an editor at them with a `$schema` key, or like this in VS Code's `settings.json`:
+//! ```json
+//! "json.schemas": [
+//!     { "fileMatch": ["mod_api.json"], "url": "./schemas/mod_api.schema.json" },
+//!     { "fileMatch": ["about.json"], "url": "./schemas/about.schema.json" }
+//! ]
+//! ```
+//!
+//! The schemas check the shape grug needs, like `mod_api.json` having both `entities` and `game_functions`,
+//! `ModAPI::validate` and `ModInfo::from_json` still check the names and types.
+//! grug also needs the keys of a function in the order `description`, `return_type`, `arguments`,
+//! which JSON Schema can't check.
+//!
+//! # Example
+//! ```no_run
+//! use grug_rs::schema;
+//!
+//! # fn main() -> Result<(), grug_rs::GrugError> {
+//! // Writes `schemas/mod_api.schema.json` and `schemas/about.schema.json`
+//! schema::generate("./schemas")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    fs::{create_dir_all, write},
+    path::Path,
+};
+
+use schemars::schema_for;
+use serde_json::{Value, to_string_pretty};
+
+#[cfg(feature = "jsonschema")]
+use crate::mod_api_type::without_location;
+use crate::{GrugError, mod_api_type::ModAPI, mod_info::ModInfo};
+
+/// The JSON Schema of `mod_api.json`
+///
+/// Generated from `ModAPI`, so it changes along with what `ModAPI::load` reads.
+pub fn mod_api() -> Value {
+    schema_for!(ModAPI).to_value()
+}
+
+/// The JSON Schema of a mod's `about.json`
+pub fn mod_info() -> Value {
+    schema_for!(ModInfo).to_value()
+}
+
+/// Writes `mod_api.schema.json` and `about.schema.json` into `out_dir`, creating it if needed
+pub fn generate<P: AsRef<Path>>(out_dir: P) -> Result<(), GrugError> {
+    let out_dir = out_dir.as_ref();
+
+    create_dir_all(out_dir).map_err(|x| GrugError::WriteSchema {
+        path: out_dir.to_path_buf(),
+        error: x.to_string(),
+    })?;
+
+    for (file_name, schema) in [
+        ("mod_api.schema.json", mod_api()),
+        ("about.schema.json", mod_info()),
+    ] {
+        let path = out_dir.join(file_name);
+
+        write(&path, to_string_pretty(&schema).unwrap()).map_err(|x| GrugError::WriteSchema {
+            path,
+            error: x.to_string(),
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Checks a `mod_api.json` against the schema
+///
+/// Every mistake is reported at the JSON pointer of where it is, like `/entities/World/description`.
+#[cfg(feature = "jsonschema")]
+pub fn check_mod_api<P: AsRef<Path>>(path: P) -> Result<(), GrugError> {
+    check(path.as_ref(), &mod_api())
+}
+
+/// Checks a mod's `about.json` against the schema, see `check_mod_api`
+#[cfg(feature = "jsonschema")]
+pub fn check_mod_info<P: AsRef<Path>>(path: P) -> Result<(), GrugError> {
+    check(path.as_ref(), &mod_info())
+}
+
+#[cfg(feature = "jsonschema")]
+fn check(path: &Path, schema: &Value) -> Result<(), GrugError> {
+    let text = std::fs::read_to_string(path).map_err(|x| GrugError::ReadFile {
+        path: path.to_path_buf(),
+        error: x.to_string(),
+    })?;
+
+    let instance: Value = serde_json::from_str(&text).map_err(|x| GrugError::Deserialize {
+        path: path.to_path_buf(),
+        line: x.line(),
+        column: x.column(),
+        error: without_location(x.to_string(), x.line(), x.column()),
+    })?;
+
+    // The schemas are generated by schemars, so they're always valid
+    let validator = jsonschema::validator_for(schema).unwrap();
+
+    let errors: Vec<String> = validator
+        .iter_errors(&instance)
+        .map(|error| match error.instance_path().as_str() {
+            "" => format!("root: {error}"),
+            pointer => format!("`{pointer}`: {error}"),
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(GrugError::SchemaViolations {
+            path: path.to_path_buf(),
+            errors,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "jsonschema"))]
+mod tests {
+    use std::{
+        fs::{read_dir, read_to_string},
+        path::PathBuf,
+    };
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn violations_of(schema: &Value, instance: &Value) -> Vec<String> {
+        let validator = jsonschema::validator_for(schema).unwrap();
+
+        validator
+            .iter_errors(instance)
+            .map(|error| format!("{}: {error}", error.instance_path()))
+            .collect()
+    }
+
+    fn violations(instance: Value) -> Vec<String> {
+        violations_of(&mod_api(), &instance)
+    }
+
+    fn about_violations(instance: Value) -> Vec<String> {
+        violations_of(&mod_info(), &instance)
+    }
+
+    fn with_world(world: Value) -> Value {
+        json!({
+            "entities": { "World": world },
+            "game_functions": {}
+        })
+    }
+
+    /// Every file in `examples` that `ModAPI::load` reads, parsed as it is on disk
+    fn example_mod_apis() -> Vec<(PathBuf, Value)> {
+        let mut mod_apis = vec![];
+
+        for entry in read_dir("examples").unwrap() {
+            let dir = entry.unwrap().path();
+
+            for file_name in ["mod_api.json", "mod_api.toml", "mod_api.yaml"] {
+                let path = dir.join(file_name);
+                if !path.is_file() {
+                    continue;
+                }
+
+                let text = read_to_string(&path).unwrap();
+                let instance: Value = match file_name {
+                    "mod_api.json" => serde_json::from_str(&text).unwrap(),
+                    #[cfg(feature = "toml")]
+                    "mod_api.toml" => toml::from_str(&text).unwrap(),
+                    #[cfg(feature = "yaml")]
+                    "mod_api.yaml" => serde_yaml::from_str(&text).unwrap(),
+                    _ => continue,
+                };
+
+                mod_apis.push((path, instance));
+            }
+        }
+
+        assert!(!mod_apis.is_empty());
+        mod_apis
+    }
+
+    #[test]
+    fn every_example_mod_api_is_loaded_and_matches() {
+        for (path, instance) in example_mod_apis() {
+            assert!(ModAPI::load(&path).is_ok(), "{}", path.display());
+            assert_eq!(
+                violations(instance),
+                Vec::<String>::new(),
+                "{}",
+                path.display()
+            );
+        }
+    }
+
+    #[test]
+    fn every_example_about_json_is_loaded_and_matches() {
+        let mut checked = 0;
+
+        for entry in read_dir("examples").unwrap() {
+            let mods = entry.unwrap().path().join("mods");
+            let Ok(mods) = read_dir(mods) else {
+                continue;
+            };
+
+            for mod_dir in mods {
+                let path = mod_dir.unwrap().path().join("about.json");
+                if !path.is_file() {
+                    continue;
+                }
+
+                let text = read_to_string(&path).unwrap();
+                assert!(ModInfo::from_json(&text).is_ok(), "{}", path.display());
+                assert_eq!(
+                    about_violations(serde_json::from_str(&text).unwrap()),
+                    Vec::<String>::new(),
+                    "{}",
+                    path.display()
+                );
+                checked += 1;
+            }
+        }
+
+        assert!(checked > 0);
+    }
+
+    fn about(changes: Value) -> Value {
+        let mut about = json!({
+            "name": "Hello",
+            "version": "1.0.0",
+            "game_version": "1.0.0",
+            "author": "grug",
+            "dependencies": { "shared_lib": "^1.2" }
+        });
+        about
+            .as_object_mut()
+            .unwrap()
+            .extend(changes.as_object().unwrap().clone());
+        about
+    }
+
+    #[test]
+    fn about_json_rejects_what_from_json_does() {
+        for instance in [
+            about(json!({ "name": "" })),
+            about(json!({ "author": 1 })),
+            about(json!({ "version": "one" })),
+            about(json!({ "extra": true })),
+            about(json!({ "dependencies": { "a": "not a version" } })),
+            about(json!({ "dependencies": { "a": 1 } })),
+        ] {
+            assert!(
+                ModInfo::from_json(&instance.to_string()).is_err(),
+                "{instance}"
+            );
+            assert_eq!(about_violations(instance.clone()).len(), 1, "{instance}");
+        }
+
+        // One for each missing field
+        assert_eq!(about_violations(json!({ "name": "Hello" })).len(), 3);
+        assert!(about_violations(about(json!({}))).is_empty());
+        assert!(about_violations(about(json!({ "dependencies": {} }))).is_empty());
+    }
+
+    #[test]
+    fn about_json_has_no_unknown_fields() {
+        assert_eq!(about_violations(about(json!({ "extra": true }))).len(), 1);
+    }
+
+    #[test]
+    fn versions_match_semver() {
+        for version in [
+            "1.2.3",
+            "0.0.0",
+            "1.2.3-beta.1",
+            "1.2.3+build.5",
+            "1.2.3-rc.1+build",
+            "",
+            "one",
+            "1.2",
+            "01.2.3",
+            "^1.2.3",
+            "v1.2.3",
+            "1.2.3-",
+            "1.2.3-01",
+        ] {
+            assert_eq!(
+                about_violations(about(json!({ "version": version }))).is_empty(),
+                semver::Version::parse(version).is_ok(),
+                "{version:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn version_requirements_match_semver() {
+        let dependency = |requirement: &str| {
+            about_violations(about(json!({ "dependencies": { "a": requirement } }))).is_empty()
+        };
+
+        for requirement in [
+            "1",
+            "1.2",
+            "1.2.3",
+            "^1.2",
+            "~1.2.3",
+            "=1.2.3",
+            ">=1.2, <1.5",
+            " > 1.2 , <= 2 ",
+            "*",
+            "1.*",
+            "1.2.x",
+            "1.2.3-beta.1",
+            "",
+            "not a version",
+            "1.2.3.4",
+            "01.2",
+            ">=1.2 <1.5",
+            "1.2,",
+            "v1.2",
+        ] {
+            assert_eq!(
+                dependency(requirement),
+                semver::VersionReq::parse(requirement).is_ok(),
+                "{requirement:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn unreadable_files() {
+        assert!(matches!(
+            check_mod_info("does_not_exist/about.json"),
+            Err(GrugError::ReadFile { .. })
+        ));
+    }
+
+    #[test]
+    fn entities_and_game_functions_are_required() {
+        assert_eq!(violations(json!({})).len(), 2);
+        assert_eq!(violations(json!({ "entities": {} })).len(), 1);
+        assert!(violations(json!({ "entities": {}, "game_functions": {} })).is_empty());
+    }
+
+    #[test]
+    fn on_functions_cant_return_anything() {
+        let world = json!({
+            "description": "The world",
+            "on_functions": {
+                "on_update": { "description": "Called every tick", "return_type": "i32" }
+            }
+        });
+
+        assert_eq!(violations(with_world(world)).len(), 1);
+    }
+
+    #[test]
+    fn arguments_cant_be_empty() {
+        let world = json!({
+            "description": "The world",
+            "on_functions": {
+                "on_update": { "description": "Called every tick", "arguments": [] }
+            }
+        });
+        let print = json!({
+            "entities": {},
+            "game_functions": { "print": { "description": "Prints", "arguments": [] } }
+        });
+
+        assert_eq!(violations(with_world(world)).len(), 1);
+        assert_eq!(violations(print).len(), 1);
+    }
+
+    #[test]
+    fn descriptions_cant_be_empty() {
+        let world = json!({ "description": "", "on_functions": {} });
+
+        assert_eq!(violations(with_world(world)).len(), 1);
+    }
+
+    #[test]
+    fn unknown_keys_and_nulls() {
+        assert_eq!(
+            violations(json!({ "entities": {}, "game_functions": {}, "version": 1 })).len(),
+            1
+        );
+        assert_eq!(
+            violations(json!({
+                "entities": {},
+                "game_functions": { "print": { "description": "Prints", "return_type": null } }
+            }))
+            .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn resource_and_entity_arguments() {
+        let spawn = |arguments: Value| {
+            violations(json!({
+                "entities": {},
+                "game_functions": { "spawn": { "description": "Spawns", "arguments": arguments } }
+            }))
+        };
+
+        assert!(
+            spawn(json!([
+                { "name": "sprite", "type": "resource", "resource_extension": ".png" },
+                { "name": "enemy", "type": "entity", "entity_type": "" },
+                { "name": "count", "type": "i32" }
+            ]))
+            .is_empty()
+        );
+        assert_eq!(
+            spawn(json!([{ "name": "sprite", "type": "resource" }])).len(),
+            1
+        );
+        assert_eq!(
+            spawn(json!([{ "name": "enemy", "type": "entity" }])).len(),
+            1
+        );
+        assert_eq!(
+            spawn(json!([{ "name": "count", "type": "i32", "entity_type": "Enemy" }])).len(),
+            1
+        );
+    }
+}